@@ -0,0 +1,69 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use advent_of_code_2020_day_2::{synthetic_lines, PasswordEntry, PasswordPolicy, PasswordPolicyMode, PreparedEntry};
+
+// Compare the policy parsers by running `cargo bench` with and without `--features lite-parser`.
+
+const LINES: usize = 1000;
+const ITERATIONS: u32 = 200;
+
+fn bench<F>(name: &str, mut f: F)
+    where F: FnMut(), {
+    // Warm up caches (and the lazily compiled regex) before measuring.
+    f();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let per_iteration = elapsed / ITERATIONS;
+    let per_line = Duration::from_nanos((per_iteration.as_nanos() / LINES as u128) as u64);
+
+    println!("{:<44} {:>12?} / {} lines {:>10?} / line", name, per_iteration, LINES, per_line);
+}
+
+fn main() {
+    let lines = synthetic_lines(LINES, 2020);
+    let policies: Vec<&str> = lines.iter()
+        .map(|line| line.split(':').next().unwrap())
+        .collect();
+    let entries: Vec<PasswordEntry> = lines.iter()
+        .filter_map(|line| PasswordEntry::parse(line))
+        .collect();
+    let prepared: Vec<PreparedEntry> = entries.iter()
+        .map(|entry| entry.clone().prepare())
+        .collect();
+
+    bench("PasswordPolicy::parse", || {
+        for policy in &policies {
+            black_box(PasswordPolicy::parse(black_box(policy)));
+        }
+    });
+    bench("PasswordEntry::parse", || {
+        for line in &lines {
+            black_box(PasswordEntry::parse(black_box(line)));
+        }
+    });
+    bench("is_valid (SledRental)", || {
+        for entry in &entries {
+            black_box(black_box(entry).is_valid(PasswordPolicyMode::SledRental));
+        }
+    });
+    bench("is_valid (TobogganCorporate)", || {
+        for entry in &entries {
+            black_box(black_box(entry).is_valid(PasswordPolicyMode::TobogganCorporate));
+        }
+    });
+    bench("PreparedEntry::is_valid (SledRental)", || {
+        for entry in &prepared {
+            black_box(black_box(entry).is_valid(PasswordPolicyMode::SledRental));
+        }
+    });
+    bench("PreparedEntry::is_valid (TobogganCorporate)", || {
+        for entry in &prepared {
+            black_box(black_box(entry).is_valid(PasswordPolicyMode::TobogganCorporate));
+        }
+    });
+}
@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use advent_of_code_2020_day_2::{PasswordEntry, PasswordPolicy, PasswordPolicyMode};
+
+const MODES: [PasswordPolicyMode; 6] = [
+    PasswordPolicyMode::SledRental,
+    PasswordPolicyMode::SledRentalExclusive,
+    PasswordPolicyMode::TobogganCorporate,
+    PasswordPolicyMode::TobogganFromEnd,
+    PasswordPolicyMode::ExactCount,
+    PasswordPolicyMode::Both,
+];
+
+fuzz_target!(|data: &[u8]| {
+    let s = String::from_utf8_lossy(data);
+
+    if let Some(entry) = PasswordEntry::parse(&s) {
+        for &mode in &MODES {
+            entry.is_valid(mode);
+        }
+    }
+
+    // Also validate the raw input against any policy parsed from its first line, so arbitrary
+    // passwords reach `validate` and not only those that survive entry parsing.
+    let (policy, password) = s.split_once('\n').unwrap_or((&s, ""));
+    if let Some(policy) = PasswordPolicy::parse(policy) {
+        for &mode in &MODES {
+            policy.validate(mode, password);
+        }
+    }
+});
@@ -0,0 +1,119 @@
+//! Loading of the binary's default options from `.aoc2day2.toml`.
+//!
+//! Only the flat subset of TOML used by the file is understood: one `key = "value"` pair per line,
+//! blank lines, and `#` comments, on their own line or after a value. All values are quoted
+//! strings without escapes: bare words, numbers, booleans, arrays and inline tables are rejected.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const CONFIG_FILE: &str = ".aoc2day2.toml";
+
+/// Default options read from a config file, as raw strings to be parsed like the matching
+/// command-line arguments.
+#[derive(Debug, Default)]
+pub struct Config {
+    pub mode: Option<String>,
+    pub input: Option<String>,
+    pub format: Option<String>,
+}
+
+impl Config {
+    /// Reads the config file at `path`, or returns an empty config if there is none.
+    pub fn load<P>(path: P) -> Result<Config, String>
+        where P: AsRef<Path>, {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(contents) => Config::parse(&contents).map_err(|err| format!("{}: {}", path.display(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(format!("cannot read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=')
+                .ok_or_else(|| format!("line {}: expected `key = \"value\"`", index + 1))?;
+            let key = key.trim();
+            let field = match key {
+                "mode" => &mut config.mode,
+                "input" => &mut config.input,
+                "format" => &mut config.format,
+                key => return Err(format!("line {}: unknown key `{}`", index + 1, key)),
+            };
+
+            let value = value.trim();
+            let (value, rest) = match value.strip_prefix('"').and_then(|value| value.split_once('"')) {
+                Some(quoted) => quoted,
+                None if is_string(value) => return Err(format!("line {}: `{}` must be a quoted string", index + 1, key)),
+                None => return Err(format!("line {}: `{}` must be a string", index + 1, key)),
+            };
+            let rest = rest.trim_start();
+            if !rest.is_empty() && !rest.starts_with('#') {
+                return Err(format!("line {}: unexpected `{}` after the value of `{}`", index + 1, rest, key));
+            }
+            *field = Some(String::from(value));
+        }
+
+        Ok(config)
+    }
+}
+
+/// Tells whether an unquoted value would be a string once quoted, rather than another TOML type.
+fn is_string(value: &str) -> bool {
+    let other_type = value.is_empty()
+        || value == "true"
+        || value == "false"
+        || value.starts_with(['[', '{', '"'])
+        || value.parse::<f64>().is_ok();
+
+    !other_type
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn test_config_parse() {
+        let config = Config::parse("# defaults\nmode = \"sled\"\n\ninput = \"../#1.txt\" # the input\nformat=\"json\"#json\n").unwrap();
+
+        assert_eq!(config.mode.as_deref(), Some("sled"));
+        assert_eq!(config.input.as_deref(), Some("../#1.txt"));
+        assert_eq!(config.format.as_deref(), Some("json"));
+    }
+
+    #[test]
+    fn test_config_parse_unknown_key() {
+        assert_eq!(Config::parse("mode = \"sled\"\ncolor = \"always\"").unwrap_err(), "line 2: unknown key `color`");
+    }
+
+    #[test]
+    fn test_config_parse_missing_equals() {
+        assert_eq!(Config::parse("mode \"sled\"").unwrap_err(), "line 1: expected `key = \"value\"`");
+    }
+
+    #[test]
+    fn test_config_parse_wrong_type() {
+        assert_eq!(Config::parse("mode = 3").unwrap_err(), "line 1: `mode` must be a string");
+        assert_eq!(Config::parse("format = true").unwrap_err(), "line 1: `format` must be a string");
+        assert_eq!(Config::parse("input = [\"a.txt\"]").unwrap_err(), "line 1: `input` must be a string");
+        assert_eq!(Config::parse("mode =").unwrap_err(), "line 1: `mode` must be a string");
+        assert_eq!(Config::parse("mode = \"sled").unwrap_err(), "line 1: `mode` must be a string");
+    }
+
+    #[test]
+    fn test_config_parse_unquoted() {
+        assert_eq!(Config::parse("input = ../x.txt").unwrap_err(), "line 1: `input` must be a quoted string");
+        assert_eq!(Config::parse("mode = sled # c").unwrap_err(), "line 1: `mode` must be a quoted string");
+        assert_eq!(Config::parse("mode = \"sled\" toboggan").unwrap_err(), "line 1: unexpected `toboggan` after the value of `mode`");
+    }
+}
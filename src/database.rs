@@ -0,0 +1,1003 @@
+use alloc::borrow::Cow;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter;
+use core::ops::AddAssign;
+#[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::thread;
+
+use crate::{CountMismatch, EntryParseError, PasswordEntry, PasswordPolicy, PasswordPolicyMode};
+
+/// Parses every line into a `PasswordEntry`, once its comment is removed with `strip_comment`,
+/// returning the parsed entries along with the 1-based line number and content of every line that
+/// could not be parsed.
+pub fn parse_database(lines: impl Iterator<Item = String>) -> (Vec<PasswordEntry>, Vec<(usize, String)>) {
+    parse_database_with(lines, |line| without_comment(line, PasswordEntry::parse))
+}
+
+/// Like `parse_database`, but parses each line with `parse`, e.g. `PasswordEntry::parse_csv`.
+/// Lines are passed to `parse` as they are, comments included, since `#` may be data in other
+/// formats.
+pub fn parse_database_with<F>(lines: impl Iterator<Item = String>, parse: F) -> (Vec<PasswordEntry>, Vec<(usize, String)>)
+    where F: Fn(&str) -> Option<PasswordEntry>, {
+    let mut entries = Vec::new();
+    let mut rejected = Vec::new();
+
+    for (index, line) in lines.enumerate() {
+        match parse(line.as_str()) {
+            Some(entry) => entries.push(entry),
+            None => rejected.push((index + 1, line)),
+        }
+    }
+
+    (entries, rejected)
+}
+
+/// Removes the comment of `line`, from its first `#` onward, along with the surrounding
+/// whitespace. A `#` written `\#` is kept as a literal `#` and does not start a comment.
+pub fn strip_comment(line: &str) -> Cow<'_, str> {
+    if !line.contains("\\#") {
+        let end = line.find('#').unwrap_or(line.len());
+        return Cow::Borrowed(line[..end].trim());
+    }
+
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'#') => {
+                stripped.push('#');
+                chars.next();
+            }
+            '#' => break,
+            _ => stripped.push(c),
+        }
+    }
+
+    Cow::Owned(String::from(stripped.trim()))
+}
+
+/// Parses `line` with `parse` once its comment is removed with `strip_comment`.
+fn without_comment<T, F>(line: &str, parse: F) -> T
+    where F: FnOnce(&str) -> T, {
+    parse(&strip_comment(line))
+}
+
+/// Lazily parses every line once its comment is removed, keeping the error of each line that
+/// cannot be parsed so that the caller can decide whether to collect, stop at, or ignore errors.
+pub fn parse_lines(lines: impl Iterator<Item = String>) -> impl Iterator<Item = Result<PasswordEntry, EntryParseError>> {
+    lines.map(|line| without_comment(line.as_str(), PasswordEntry::try_parse))
+}
+
+/// Like `parse_lines`, but for databases that put each policy (`1-3 a`) on its own line and the
+/// password on the next one. A trailing policy line without a password is a `MissingPassword`
+/// error.
+pub fn parse_line_pairs(mut lines: impl Iterator<Item = String>) -> impl Iterator<Item = Result<PasswordEntry, EntryParseError>> {
+    iter::from_fn(move || {
+        let policy = lines.next()?;
+        let entry = match lines.next() {
+            Some(password) => PasswordPolicy::try_parse(policy.trim())
+                .map(|policy| PasswordEntry::new(policy, password.trim()))
+                .map_err(EntryParseError::from),
+            None => Err(EntryParseError::MissingPassword),
+        };
+
+        Some(entry)
+    })
+}
+
+#[cfg(feature = "std")]
+/// Reads the lines of `reader` like `BufRead::lines`, except that a line that is not valid UTF-8
+/// does not end the reading: it is returned as `Ok(Err(line))`, with its invalid sequences
+/// replaced by `U+FFFD`.
+pub fn lines_lossy<R: BufRead>(reader: R) -> impl Iterator<Item = io::Result<Result<String, String>>> {
+    reader.split(b'\n').map(|line| {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+
+        Ok(String::from_utf8(line).map_err(|err| String::from_utf8_lossy(err.as_bytes()).into_owned()))
+    })
+}
+
+#[cfg(feature = "std")]
+/// Reads and parses every line of `reader` with `parse`, as `parse_database_with` does, except
+/// that comments are removed with `strip_comment` first. Lines that are not valid UTF-8 are
+/// rejected, decoded lossily. Reading stops at the first I/O error.
+pub fn parse_reader<R, F>(reader: R, parse: F) -> (Vec<PasswordEntry>, Vec<(usize, String)>)
+    where R: BufRead, F: Fn(&str) -> Option<PasswordEntry>, {
+    let mut entries = Vec::new();
+    let mut rejected = Vec::new();
+
+    for (index, line) in lines_lossy(reader).map_while(|line| line.ok()).enumerate() {
+        match line {
+            Ok(line) => match without_comment(line.as_str(), &parse) {
+                Some(entry) => entries.push(entry),
+                None => rejected.push((index + 1, line)),
+            },
+            Err(line) => rejected.push((index + 1, line)),
+        }
+    }
+
+    (entries, rejected)
+}
+
+#[cfg(feature = "std")]
+/// Lazily parses the lines of `reader` once their comment is removed, skipping lines that cannot
+/// be parsed or are not valid UTF-8. Reading stops at the first I/O error.
+pub fn read_entries<R: BufRead>(reader: R) -> impl Iterator<Item = PasswordEntry> {
+    lines_lossy(reader)
+        .map_while(|line| line.ok())
+        .filter_map(|line| line.ok())
+        .filter_map(|line| without_comment(line.as_str(), PasswordEntry::parse))
+}
+
+#[cfg(feature = "std")]
+/// Opens the file at `path` and lazily parses its lines with `read_entries`.
+pub fn read_entries_from_file<P>(path: P) -> io::Result<impl Iterator<Item = PasswordEntry>>
+    where P: AsRef<Path>, {
+    let file = File::open(path)?;
+    Ok(read_entries(BufReader::new(file)))
+}
+
+#[cfg(feature = "std")]
+/// Parses each line of `reader` with `parse` and validates it with `is_valid`, writing `VALID`,
+/// `INVALID` or the parse error for it to `writer`, until the end of the input. Comments are
+/// removed with `strip_comment` first, and blank or comment-only lines are skipped.
+pub fn run_repl<R, W, P, V>(reader: R, mut writer: W, parse: P, is_valid: V) -> io::Result<()>
+    where R: BufRead, W: Write, P: Fn(&str) -> Result<PasswordEntry, EntryParseError>, V: Fn(&PasswordEntry) -> bool, {
+    for line in reader.lines() {
+        let line = line?;
+        let entry = strip_comment(line.as_str());
+        if entry.is_empty() {
+            continue;
+        }
+
+        match parse(&entry) {
+            Ok(entry) if is_valid(&entry) => writeln!(writer, "VALID")?,
+            Ok(_) => writeln!(writer, "INVALID")?,
+            Err(err) => writeln!(writer, "error: cannot parse `{}`: {}", line, err)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds `valid` to iterators over entries, to filter them fluently:
+/// `entries.into_iter().valid(mode).count()`.
+pub trait PasswordEntryIterExt: Iterator<Item = PasswordEntry> + Sized {
+    /// Lazily keeps only the entries that are valid under `mode`.
+    fn valid(self, mode: PasswordPolicyMode) -> ValidEntries<Self> {
+        ValidEntries { iter: self, mode }
+    }
+}
+
+impl<I: Iterator<Item = PasswordEntry>> PasswordEntryIterExt for I {}
+
+/// The iterator returned by `PasswordEntryIterExt::valid`.
+pub struct ValidEntries<I> {
+    iter: I,
+    mode: PasswordPolicyMode,
+}
+
+impl<I: Iterator<Item = PasswordEntry>> Iterator for ValidEntries<I> {
+    type Item = PasswordEntry;
+
+    fn next(&mut self) -> Option<PasswordEntry> {
+        let mode = self.mode;
+        self.iter.find(|e| e.is_valid(mode))
+    }
+}
+
+#[cfg(feature = "std")]
+/// Removes exact duplicate entries, keeping the first occurrence of each in its original order.
+pub fn unique_entries(entries: Vec<PasswordEntry>) -> Vec<PasswordEntry> {
+    let mut seen = HashSet::new();
+
+    entries.into_iter()
+        .filter(|e| seen.insert(e.clone()))
+        .collect()
+}
+
+#[cfg(feature = "std")]
+/// Appends the entries of `b` to those of `a`. With `dedupe`, only the first occurrence of each
+/// entry is kept, as with `unique_entries`.
+pub fn merge(mut a: Vec<PasswordEntry>, b: Vec<PasswordEntry>, dedupe: bool) -> Vec<PasswordEntry> {
+    a.extend(b);
+    if dedupe {
+        unique_entries(a)
+    } else {
+        a
+    }
+}
+
+#[cfg(feature = "std")]
+/// Hashes all entries in order, to tell whether a database changed between runs. The hash is
+/// stable from one run to the next, but may change with the Rust version.
+pub fn database_fingerprint(entries: &[PasswordEntry]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Sorts entries by policy, then by password, for deterministic output.
+pub fn sort_entries(entries: &mut [PasswordEntry]) {
+    entries.sort();
+}
+
+#[cfg(feature = "std")]
+/// Returns the entries whose pattern is in `allow`, in their original order.
+pub fn filter_by_pattern<'a>(entries: &'a [PasswordEntry], allow: &HashSet<char>) -> Vec<&'a PasswordEntry> {
+    entries.iter()
+        .filter(|e| allow.contains(&e.policy.pattern))
+        .collect()
+}
+
+#[cfg(feature = "std")]
+/// Returns the entries whose pattern is not in `deny`, in their original order.
+pub fn exclude_by_pattern<'a>(entries: &'a [PasswordEntry], deny: &HashSet<char>) -> Vec<&'a PasswordEntry> {
+    entries.iter()
+        .filter(|e| !deny.contains(&e.policy.pattern))
+        .collect()
+}
+
+/// Returns every entry that is not valid under `mode`.
+pub fn invalid_entries(entries: &[PasswordEntry], mode: PasswordPolicyMode) -> Vec<&PasswordEntry> {
+    entries.iter()
+        .filter(|e| !e.is_valid(mode))
+        .collect()
+}
+
+/// Checks that exactly `expected_valid` entries are valid under `mode`.
+pub fn assert_counts(entries: &[PasswordEntry], mode: PasswordPolicyMode, expected_valid: usize) -> Result<(), CountMismatch> {
+    let actual = entries.iter()
+        .filter(|e| e.is_valid(mode))
+        .count();
+    if actual != expected_valid {
+        return Err(CountMismatch { mode, expected: expected_valid, actual });
+    }
+
+    Ok(())
+}
+
+/// Returns the first entry that is not valid under `mode`, without validating the entries after
+/// it, or `None` if all are valid.
+pub fn first_invalid(entries: &[PasswordEntry], mode: PasswordPolicyMode) -> Option<&PasswordEntry> {
+    entries.iter().find(|e| !e.is_valid(mode))
+}
+
+/// Splits the entries into those that are valid under `mode` and those that are not, in a single
+/// pass: returns `(valid, invalid)`, both in their original order.
+pub fn partition_valid(entries: Vec<PasswordEntry>, mode: PasswordPolicyMode) -> (Vec<PasswordEntry>, Vec<PasswordEntry>) {
+    entries.into_iter().partition(|e| e.is_valid(mode))
+}
+
+/// Returns the validity of every entry under `mode`, in the same order as `entries`.
+pub fn validate_all(entries: &[PasswordEntry], mode: PasswordPolicyMode) -> Vec<bool> {
+    entries.iter()
+        .map(|e| e.is_valid(mode))
+        .collect()
+}
+
+/// Validates every entry against the whole database: an entry is valid when its pattern occurs
+/// between `first` and `second` times, both inclusive, across all passwords.
+pub fn validate_global(entries: &[PasswordEntry]) -> Vec<bool> {
+    let mut frequencies = BTreeMap::new();
+    for c in entries.iter().flat_map(|e| e.password.chars()) {
+        *frequencies.entry(c).or_insert(0u32) += 1;
+    }
+
+    entries.iter()
+        .map(|e| {
+            let count = frequencies.get(&e.policy.pattern).copied().unwrap_or(0);
+            e.policy.first <= count && count <= e.policy.second
+        })
+        .collect()
+}
+
+/// Like `invalid_entries`, but pairs each entry with a short explanation of the failure.
+pub fn invalid_entries_with_reasons(entries: &[PasswordEntry], mode: PasswordPolicyMode) -> Vec<(&PasswordEntry, String)> {
+    entries.iter()
+        .filter_map(|e| e.failure_reason(mode).map(|reason| (e, reason)))
+        .collect()
+}
+
+/// Counts the entries of a database that are valid under a given mode. Summaries of several
+/// databases add up with `+=`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ValidationSummary {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+}
+
+pub fn summarize(entries: &[PasswordEntry], mode: PasswordPolicyMode) -> ValidationSummary {
+    summarize_by(entries, |e| e.is_valid(mode))
+}
+
+/// Like `summarize`, but patterns match the passwords' characters regardless of case.
+pub fn summarize_ignore_case(entries: &[PasswordEntry], mode: PasswordPolicyMode) -> ValidationSummary {
+    summarize_by(entries, |e| e.is_valid_ignore_case(mode))
+}
+
+fn summarize_by<F>(entries: &[PasswordEntry], is_valid: F) -> ValidationSummary
+    where F: Fn(&PasswordEntry) -> bool, {
+    let total = entries.len();
+    let valid = entries.iter()
+        .filter(|e| is_valid(e))
+        .count();
+
+    ValidationSummary {
+        total,
+        valid,
+        invalid: total - valid,
+    }
+}
+
+/// Parses `line` and validates it under `mode`, or returns `None` if it cannot be parsed.
+pub fn validate_line(line: &str, mode: PasswordPolicyMode) -> Option<bool> {
+    PasswordEntry::parse(line).map(|entry| entry.is_valid(mode))
+}
+
+/// Counts how many policies use each pattern character.
+pub fn pattern_histogram(entries: &[PasswordEntry]) -> BTreeMap<char, usize> {
+    let mut histogram = BTreeMap::new();
+    for entry in entries {
+        *histogram.entry(entry.policy.pattern).or_insert(0) += 1;
+    }
+
+    histogram
+}
+
+/// Buckets the entries by the pattern of their policy, keeping their order within each bucket.
+pub fn group_by_pattern(entries: Vec<PasswordEntry>) -> BTreeMap<char, Vec<PasswordEntry>> {
+    let mut groups = BTreeMap::new();
+    for entry in entries {
+        groups.entry(entry.policy.pattern).or_insert_with(Vec::new).push(entry);
+    }
+
+    groups
+}
+
+/// Counts the distinct characters of `password`.
+pub fn distinct_char_count(password: &str) -> usize {
+    password.chars().collect::<BTreeSet<char>>().len()
+}
+
+/// Counts the valid entries under both puzzle rules in a single pass, returning
+/// `(sled_valid, toboggan_valid)`.
+pub fn count_both(entries: &[PasswordEntry]) -> (usize, usize) {
+    entries.iter().fold((0, 0), |(sled, toboggan), e| {
+        (
+            sled + e.is_valid(PasswordPolicyMode::SledRental) as usize,
+            toboggan + e.is_valid(PasswordPolicyMode::TobogganCorporate) as usize,
+        )
+    })
+}
+
+#[cfg(feature = "std")]
+/// Counts the valid entries, splitting the work across the available cores.
+pub fn count_valid_parallel(entries: &[PasswordEntry], mode: PasswordPolicyMode) -> usize {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = entries.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = entries.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().filter(|e| e.is_valid(mode)).count()))
+            .collect();
+
+        workers.into_iter()
+            .map(|worker| worker.join().unwrap())
+            .sum()
+    })
+}
+
+/// Classifies entries under `SledRental` rules by how their occurrence count compares to the
+/// allowed range.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct SledRentalBreakdown {
+    pub below_min: usize,
+    pub above_max: usize,
+    pub valid: usize,
+}
+
+pub fn breakdown(entries: &[PasswordEntry]) -> SledRentalBreakdown {
+    let mut breakdown = SledRentalBreakdown::default();
+
+    for entry in entries {
+        let count = entry.policy.occurrences(entry.password.as_str());
+        if count < entry.policy.first {
+            breakdown.below_min += 1;
+        } else if count > entry.policy.second {
+            breakdown.above_max += 1;
+        } else {
+            breakdown.valid += 1;
+        }
+    }
+
+    breakdown
+}
+
+/// Parses and validates `lines` one at a time, without collecting the entries. Lines that cannot
+/// be parsed are skipped and not counted.
+pub fn count_valid(lines: impl Iterator<Item = String>, mode: PasswordPolicyMode) -> ValidationSummary {
+    let mut total = 0;
+    let mut valid = 0;
+
+    for entry in lines.filter_map(|line| PasswordEntry::parse(line.as_str())) {
+        total += 1;
+        if entry.is_valid(mode) {
+            valid += 1;
+        }
+    }
+
+    ValidationSummary {
+        total,
+        valid,
+        invalid: total - valid,
+    }
+}
+
+impl ValidationSummary {
+    /// Formats the summary as a JSON object, e.g. `{"total":3,"valid":2,"invalid":1}`.
+    pub fn to_json(&self) -> String {
+        format!(r#"{{"total":{},"valid":{},"invalid":{}}}"#, self.total, self.valid, self.invalid)
+    }
+
+    /// The share of valid passwords, between 0 and 1. An empty summary has a ratio of 0.
+    pub fn valid_ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        self.valid as f64 / self.total as f64
+    }
+}
+
+impl AddAssign for ValidationSummary {
+    fn add_assign(&mut self, other: ValidationSummary) {
+        self.total += other.total;
+        self.valid += other.valid;
+        self.invalid += other.invalid;
+    }
+}
+
+impl fmt::Display for ValidationSummary {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "There are {} / {} valid passwords ({} invalid passwords)", self.valid, self.total, self.invalid)
+    }
+}
+
+/// Generates `count` well-formed database lines from `seed`; the same seed always yields the same
+/// lines. Useful to benchmark or stress-test the parser without a real puzzle input.
+pub fn synthetic_lines(count: usize, seed: u64) -> Vec<String> {
+    let mut state = seed;
+    let mut next = move |bound: u64| {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 33) % bound
+    };
+
+    (0..count)
+        .map(|_| {
+            let first = next(10) + 1;
+            let second = first + next(10);
+            let pattern = (b'a' + next(26) as u8) as char;
+            let length = next(20) + 1;
+            let password: String = (0..length).map(|_| (b'a' + next(26) as u8) as char).collect();
+
+            format!("{}-{} {}: {}", first, second, pattern, password)
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::PasswordPolicyMode::{SledRental, SledRentalExclusive, TobogganCorporate};
+
+    use std::collections::HashSet;
+    use std::io::Cursor;
+
+    use crate::*;
+
+    #[test]
+    fn test_parse_database() {
+        let lines = vec!["1-3 a: abcde", "", "1-3 b: cdefg", "oops", "2-9 c: ccccccccc"];
+        let (entries, rejected) = parse_database(lines.into_iter().map(String::from));
+
+        assert_eq!(entries, vec![
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"),
+            PasswordEntry::new(PasswordPolicy::new('b', 1, 3), "cdefg"),
+            PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc"),
+        ]);
+        assert_eq!(rejected, vec![(2, String::from("")), (4, String::from("oops"))]);
+    }
+
+    #[test]
+    fn test_strip_comment() {
+        assert_eq!(strip_comment("1-3 a: abcde # the first example"), "1-3 a: abcde");
+        assert_eq!(strip_comment("1-3 a: abcde#"), "1-3 a: abcde");
+        assert_eq!(strip_comment("# a standalone comment"), "");
+        assert_eq!(strip_comment("   "), "");
+        assert_eq!(strip_comment("1-3 \\#: a\\#b # with escapes"), "1-3 #: a#b");
+        assert_eq!(strip_comment("1-3 a: a\\b"), "1-3 a: a\\b");
+
+        let entry = PasswordEntry::parse(&strip_comment("1-3 a: abcde # the first example"));
+        assert_eq!(entry, Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde")));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        use PasswordPolicyParseError::*;
+
+        let lines = vec!["1-3 a: abcde", "", "1-x b: cdefg", "2-9 c: ccccccccc", "1-3 ab: abc"];
+        let results: Vec<_> = parse_lines(lines.into_iter().map(String::from)).collect();
+
+        assert_eq!(results, vec![
+            Ok(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde")),
+            Err(EntryParseError::MissingSeparator),
+            Err(EntryParseError::Policy(InvalidRange)),
+            Ok(PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc")),
+            Err(EntryParseError::Policy(BadPattern)),
+        ]);
+
+        let lines = vec!["1-3 a: abcde", "oops", "2-9 c: ccccccccc"];
+        let entries: Result<Vec<_>, _> = parse_lines(lines.into_iter().map(String::from)).collect();
+        assert_eq!(entries, Err(EntryParseError::MissingSeparator));
+    }
+
+    #[test]
+    fn test_database_fingerprint() {
+        let (entries, _) = parse_database(synthetic_lines(20, 3).into_iter());
+        let (same, _) = parse_database(synthetic_lines(20, 3).into_iter());
+        assert_eq!(database_fingerprint(&entries), database_fingerprint(&same));
+
+        let mut reordered = entries.clone();
+        reordered.swap(0, 1);
+        assert_ne!(database_fingerprint(&entries), database_fingerprint(&reordered));
+        assert_ne!(database_fingerprint(&entries), database_fingerprint(&entries[1..]));
+    }
+
+    #[test]
+    fn test_filter_by_pattern() {
+        let (entries, _) = parse_database(vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "1-1 a: b"].into_iter().map(String::from));
+        let allow: HashSet<char> = ['a', 'c'].iter().cloned().collect();
+
+        let filtered = filter_by_pattern(&entries, &allow);
+        assert_eq!(filtered, vec![&entries[0], &entries[2], &entries[3]]);
+        assert_eq!(filtered.iter().filter(|e| e.is_valid(SledRental)).count(), 2);
+        assert!(filter_by_pattern(&entries, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_exclude_by_pattern() {
+        let (entries, _) = parse_database(vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "1-1 a: b"].into_iter().map(String::from));
+        let deny: HashSet<char> = ['a', 'c'].iter().cloned().collect();
+
+        assert_eq!(exclude_by_pattern(&entries, &deny), vec![&entries[1]]);
+        assert_eq!(exclude_by_pattern(&entries, &HashSet::new()).len(), 4);
+    }
+
+    #[test]
+    fn test_parse_line_pairs() {
+        let lines = vec!["1-3 a", "abcde", "2-9 c", "ccccccccc"];
+        let results: Vec<_> = parse_line_pairs(lines.into_iter().map(String::from)).collect();
+        assert_eq!(results, vec![
+            Ok(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde")),
+            Ok(PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc")),
+        ]);
+
+        let lines = vec!["1-3 a", "abcde", "1-x b", "cdefg", "2-9 c"];
+        let results: Vec<_> = parse_line_pairs(lines.into_iter().map(String::from)).collect();
+        assert_eq!(results, vec![
+            Ok(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde")),
+            Err(EntryParseError::Policy(PasswordPolicyParseError::InvalidRange)),
+            Err(EntryParseError::MissingPassword),
+        ]);
+    }
+
+    #[test]
+    fn test_readers_strip_comments() {
+        let expected = PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde");
+        let lines = || vec!["1-3 a: abcde # note", "# only a comment"].into_iter().map(String::from);
+
+        assert_eq!(parse_database(lines()), (vec![expected.clone()], vec![(2, String::from("# only a comment"))]));
+        assert_eq!(parse_lines(lines()).next(), Some(Ok(expected.clone())));
+        assert_eq!(parse_reader(Cursor::new("1-3 a: abcde # note\n"), PasswordEntry::parse).0, vec![expected.clone()]);
+        assert_eq!(read_entries(Cursor::new("1-3 a: abcde # note\n")).collect::<Vec<_>>(), vec![expected]);
+
+        let (entries, _) = parse_database_with(lines(), PasswordEntry::parse);
+        assert_eq!(entries[0].password(), "abcde # note");
+
+        let mut transcript = Vec::new();
+        run_repl(Cursor::new("1-3 a: abcde # note\n# only a comment\n"), &mut transcript, PasswordEntry::try_parse, |entry| {
+            entry.password() == "abcde"
+        }).unwrap();
+        assert_eq!(String::from_utf8(transcript).unwrap(), "VALID\n");
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let input = Cursor::new("1-3 a: abcde\n1-3 b: cdefg\n\n2-9 c: ccccccccc\n".as_bytes());
+        let (entries, rejected) = parse_reader(input, PasswordEntry::parse);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(rejected, vec![(3, String::from(""))]);
+        assert_eq!(summarize(&entries, SledRental), ValidationSummary { total: 3, valid: 2, invalid: 1 });
+    }
+
+    #[test]
+    fn test_lines_lossy() {
+        let lines: Vec<_> = lines_lossy(Cursor::new(b"1-3 a: abcde\r\n1-3 b: \xffcd\nlast".to_vec()))
+            .map(|line| line.unwrap())
+            .collect();
+
+        assert_eq!(lines, vec![
+            Ok(String::from("1-3 a: abcde")),
+            Err(String::from("1-3 b: \u{FFFD}cd")),
+            Ok(String::from("last")),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_reader_invalid_utf8() {
+        let reader = Cursor::new(b"1-3 a: abcde\n1-3 b: \xff\n2-9 c: ccccccccc\n".to_vec());
+        let (entries, rejected) = parse_reader(reader, PasswordEntry::parse);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(rejected, vec![(2, String::from("1-3 b: \u{FFFD}"))]);
+        assert_eq!(read_entries(Cursor::new(b"\xff\n1-3 a: abcde\n".to_vec())).count(), 1);
+    }
+
+    #[test]
+    fn test_read_entries() {
+        let input = Cursor::new("1-3 a: abcde\nnot an entry\n2-9 c: ccccccccc".as_bytes());
+        let entries: Vec<PasswordEntry> = read_entries(input).collect();
+
+        assert_eq!(entries, vec![
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"),
+            PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc"),
+        ]);
+    }
+
+    #[test]
+    fn test_read_entries_from_file() {
+        assert!(read_entries_from_file("./does-not-exist").is_err());
+        assert_eq!(read_entries_from_file("./input").unwrap().count(), 1000);
+    }
+
+    #[test]
+    fn test_password_entry_iter_valid() {
+        let lines = ["1-3 a: abcde", "1-3 b: cdefg", "not an entry", "2-9 c: ccccccccc"];
+        let valid: Vec<PasswordEntry> = lines.iter()
+            .filter_map(|line| PasswordEntry::parse(line))
+            .valid(SledRental)
+            .collect();
+
+        assert_eq!(valid, vec![
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"),
+            PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc"),
+        ]);
+        assert_eq!(read_entries(Cursor::new(lines.join("\n"))).valid(TobogganCorporate).count(), 1);
+    }
+
+    #[test]
+    fn test_unique_entries() {
+        let lines = vec!["1-3 b: cdefg", "1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "1-3 a: abcde", "1-3 a: abcdef"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+        let unique = unique_entries(entries);
+
+        assert_eq!(unique.len(), 4);
+        assert_eq!(unique, vec![
+            PasswordEntry::new(PasswordPolicy::new('b', 1, 3), "cdefg"),
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"),
+            PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc"),
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcdef"),
+        ]);
+    }
+
+    #[test]
+    fn test_validate_global() {
+        // Across all passwords, `a` occurs 4 times, `b` 2 times, `c` 11 times and `z` never.
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "4-4 a: aaa", "1-1 z: b"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+
+        assert_eq!(validate_global(&entries), vec![false, true, false, true, false]);
+        assert!(validate_global(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_assert_counts() {
+        let (entries, _) = parse_database(SAMPLE_INPUT.lines().map(String::from));
+
+        assert_eq!(assert_counts(&entries, SledRental, 2), Ok(()));
+        assert_eq!(assert_counts(&entries, TobogganCorporate, 1), Ok(()));
+
+        let err = assert_counts(&entries, TobogganCorporate, 2).unwrap_err();
+        assert_eq!(err, CountMismatch { mode: TobogganCorporate, expected: 2, actual: 1 });
+        assert_eq!(err.to_string(), "expected 2 valid passwords in toboggan mode, found 1");
+    }
+
+    #[test]
+    fn test_group_by_pattern() {
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "4-5 a: aaaa", "1-1 b: b", "3-3 a: aaa"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+        let groups = group_by_pattern(entries.clone());
+
+        assert_eq!(groups.keys().collect::<Vec<_>>(), vec![&'a', &'b', &'c']);
+        assert_eq!(groups[&'a'], vec![entries[0].clone(), entries[3].clone(), entries[5].clone()]);
+        assert_eq!(groups[&'b'], vec![entries[1].clone(), entries[4].clone()]);
+        assert_eq!(groups[&'c'], vec![entries[2].clone()]);
+        assert!(group_by_pattern(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_first_invalid() {
+        let mut entries = vec![PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"); 10_000];
+        entries[2] = PasswordEntry::new(PasswordPolicy::new('b', 1, 3), "cdefg");
+        entries[5000] = PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccccc");
+
+        let invalid = first_invalid(&entries, SledRental).unwrap();
+        assert!(std::ptr::eq(invalid, &entries[2]));
+        assert_eq!(first_invalid(&entries[3..5000], SledRental), None);
+    }
+
+    #[test]
+    fn test_partition_valid() {
+        let (entries, _) = parse_database(SAMPLE_INPUT.lines().map(String::from));
+
+        let (valid, invalid) = partition_valid(entries.clone(), SledRental);
+        assert_eq!(valid, vec![entries[0].clone(), entries[2].clone()]);
+        assert_eq!(invalid, vec![entries[1].clone()]);
+
+        let (valid, invalid) = partition_valid(entries.clone(), TobogganCorporate);
+        assert_eq!(valid, vec![entries[0].clone()]);
+        assert_eq!(invalid, vec![entries[1].clone(), entries[2].clone()]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let a = vec![
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"),
+            PasswordEntry::new(PasswordPolicy::new('b', 1, 3), "cdefg"),
+        ];
+        let b = vec![
+            PasswordEntry::new(PasswordPolicy::new('b', 1, 3), "cdefg"),
+            PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc"),
+        ];
+
+        let merged = merge(a.clone(), b.clone(), false);
+        assert_eq!(merged, vec![a[0].clone(), a[1].clone(), b[0].clone(), b[1].clone()]);
+
+        let merged = merge(a.clone(), b.clone(), true);
+        assert_eq!(merged, vec![a[0].clone(), a[1].clone(), b[1].clone()]);
+    }
+
+    #[test]
+    fn test_sort_entries() {
+        let lines = vec!["2-9 c: ccccccccc", "1-3 b: cdefg", "1-4 a: abcde", "1-3 a: zzz", "1-3 a: abcde"];
+        let (mut entries, _) = parse_database(lines.into_iter().map(String::from));
+        sort_entries(&mut entries);
+
+        let sorted: Vec<String> = entries.iter().map(|e| e.to_string()).collect();
+        assert_eq!(sorted, vec!["1-3 a: abcde", "1-3 a: zzz", "1-3 b: cdefg", "1-4 a: abcde", "2-9 c: ccccccccc"]);
+    }
+
+    #[test]
+    fn test_invalid_entries() {
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "1-2 d: dd", "1-3 e: xyz"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+
+        assert_eq!(invalid_entries(&entries, SledRental), vec![&entries[1], &entries[4]]);
+        assert_eq!(invalid_entries(&entries, TobogganCorporate), vec![&entries[1], &entries[2], &entries[3], &entries[4]]);
+        assert!(invalid_entries(&entries[..1], SledRental).is_empty());
+    }
+
+    #[test]
+    fn test_validate_all() {
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+
+        assert_eq!(validate_all(&entries, SledRental), vec![true, false, true]);
+        assert_eq!(validate_all(&entries, TobogganCorporate), vec![true, false, false]);
+
+        for &mode in &[SledRental, SledRentalExclusive, TobogganCorporate] {
+            let validity = validate_all(&entries, mode);
+            assert_eq!(validity.len(), entries.len());
+            for (entry, &valid) in entries.iter().zip(&validity) {
+                assert_eq!(valid, entry.is_valid(mode));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_entries_with_reasons() {
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "1-3 a: aaaa", "5-6 a: a"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+
+        assert_eq!(invalid_entries_with_reasons(&entries, SledRental), vec![
+            (&entries[1], String::from("count 0 not in 1..=3")),
+            (&entries[3], String::from("count 4 not in 1..=3")),
+            (&entries[4], String::from("count 1 not in 5..=6")),
+        ]);
+        assert_eq!(invalid_entries_with_reasons(&entries, SledRentalExclusive)[0].1, "count 0 not in 1..3");
+        assert_eq!(invalid_entries_with_reasons(&entries, TobogganCorporate), vec![
+            (&entries[1], String::from("neither position 1 nor 3 matches")),
+            (&entries[2], String::from("positions 2 and 9 both match")),
+            (&entries[3], String::from("positions 1 and 3 both match")),
+            (&entries[4], String::from("neither position 5 nor 6 matches")),
+        ]);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let entries: Vec<PasswordEntry> = ["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc"].iter()
+            .filter_map(|line| PasswordEntry::parse(line))
+            .collect();
+
+        assert_eq!(summarize(&entries, SledRental), ValidationSummary { total: 3, valid: 2, invalid: 1 });
+        assert_eq!(summarize(&entries, TobogganCorporate), ValidationSummary { total: 3, valid: 1, invalid: 2 });
+        assert_eq!(summarize(&[], SledRental), ValidationSummary { total: 0, valid: 0, invalid: 0 });
+    }
+
+    #[test]
+    fn test_summarize_ignore_case() {
+        let entries: Vec<PasswordEntry> = ["3-3 a: AbAba", "1-2 a: AbAba", "1-3 b: cdefg"].iter()
+            .filter_map(|line| PasswordEntry::parse(line))
+            .collect();
+
+        assert_eq!(summarize(&entries[..1], SledRental), ValidationSummary { total: 1, valid: 0, invalid: 1 });
+        assert_eq!(summarize_ignore_case(&entries[..1], SledRental), ValidationSummary { total: 1, valid: 1, invalid: 0 });
+        assert_eq!(summarize_ignore_case(&entries, SledRental), ValidationSummary { total: 3, valid: 1, invalid: 2 });
+    }
+
+    #[test]
+    fn test_validate_line() {
+        assert_eq!(validate_line("1-3 a: abcde", SledRental), Some(true));
+        assert_eq!(validate_line("1-3 a: abcde", TobogganCorporate), Some(true));
+        assert_eq!(validate_line("1-3 b: cdefg", SledRental), Some(false));
+        assert_eq!(validate_line("2-9 c: ccccccccc", TobogganCorporate), Some(false));
+        assert_eq!(validate_line("1-3 b cdefg", SledRental), None);
+        assert_eq!(validate_line("", TobogganCorporate), None);
+    }
+
+    #[test]
+    fn test_run_repl() {
+        let input = Cursor::new("1-3 a: abcde\n1-3 b: cdefg\n\nnot an entry\n2-9 c: ccccccccc\n");
+        let mut transcript = Vec::new();
+        run_repl(input, &mut transcript, PasswordEntry::try_parse, |entry| entry.is_valid(SledRental)).unwrap();
+
+        assert_eq!(String::from_utf8(transcript).unwrap(), concat!(
+            "VALID\nINVALID\n",
+            "error: cannot parse `not an entry`: missing separator between the policy and the password\n",
+            "VALID\n",
+        ));
+    }
+
+    #[test]
+    fn test_pattern_histogram() {
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 a: ccccccccc", "1-2 é: éé", "4-5 a: aaaa", "1-1 b: b"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+        let histogram = pattern_histogram(&entries);
+
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), vec![('a', 3), ('b', 2), ('é', 1)]);
+        assert!(pattern_histogram(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_distinct_char_count() {
+        assert_eq!(distinct_char_count(""), 0);
+        assert_eq!(distinct_char_count("abcde"), 5);
+        assert_eq!(distinct_char_count("ccccccccc"), 1);
+        assert_eq!(distinct_char_count("éeée"), 2);
+        assert_eq!(distinct_char_count("字🦀字🦀a"), 3);
+    }
+
+    #[test]
+    fn test_count_both() {
+        let entries: Vec<PasswordEntry> = ["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc"].iter()
+            .filter_map(|line| PasswordEntry::parse(line))
+            .collect();
+
+        assert_eq!(count_both(&entries), (2, 1));
+        assert_eq!(count_both(&[]), (0, 0));
+    }
+
+    #[test]
+    fn test_count_valid() {
+        let lines = synthetic_lines(500, 7);
+        let (entries, _) = parse_database(lines.clone().into_iter());
+
+        for &mode in &[SledRental, TobogganCorporate] {
+            assert_eq!(count_valid(lines.clone().into_iter(), mode), summarize(&entries, mode));
+        }
+
+        let lines = vec!["1-3 a: abcde", "garbage", "1-3 b: cdefg"];
+        let summary = count_valid(lines.into_iter().map(String::from), SledRental);
+        assert_eq!(summary, ValidationSummary { total: 2, valid: 1, invalid: 1 });
+    }
+
+    #[test]
+    fn test_count_valid_parallel() {
+        let (entries, _) = parse_database(synthetic_lines(100_000, 3).into_iter());
+
+        for &mode in &[SledRental, TobogganCorporate] {
+            assert_eq!(count_valid_parallel(&entries, mode), summarize(&entries, mode).valid);
+        }
+        assert_eq!(count_valid_parallel(&[], SledRental), 0);
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc", "1-2 d: dddd", "2-3 e: e", "1-1 f: ff"];
+        let (entries, _) = parse_database(lines.into_iter().map(String::from));
+
+        assert_eq!(breakdown(&entries), SledRentalBreakdown { below_min: 2, above_max: 2, valid: 2 });
+        assert_eq!(breakdown(&entries).valid, summarize(&entries, SledRental).valid);
+        assert_eq!(breakdown(&[]), SledRentalBreakdown::default());
+    }
+
+    #[test]
+    fn test_validation_summary_to_string() {
+        let summary = ValidationSummary { total: 3, valid: 2, invalid: 1 };
+        assert_eq!(summary.to_string(), "There are 2 / 3 valid passwords (1 invalid passwords)");
+    }
+
+    #[test]
+    fn test_validation_summary_to_json() {
+        let summary = ValidationSummary { total: 3, valid: 2, invalid: 1 };
+        assert_eq!(summary.to_json(), r#"{"total":3,"valid":2,"invalid":1}"#);
+    }
+
+    #[test]
+    fn test_validation_summary_valid_ratio() {
+        let summary = ValidationSummary { total: 4, valid: 3, invalid: 1 };
+        assert_eq!(summary.valid_ratio(), 0.75);
+        assert_eq!(ValidationSummary::default().valid_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_synthetic_lines() {
+        let lines = synthetic_lines(1000, 42);
+        assert_eq!(lines.len(), 1000);
+        assert_eq!(lines, synthetic_lines(1000, 42));
+        assert_ne!(lines, synthetic_lines(1000, 43));
+        assert!(lines.iter().all(|line| PasswordEntry::parse(line).is_some()));
+    }
+
+    #[test]
+    fn test_parse_database_with_csv() {
+        let lines = vec!["1,3,a,abcde", "1-3 b: cdefg"];
+        let (entries, rejected) = parse_database_with(lines.into_iter().map(String::from), PasswordEntry::parse_csv);
+
+        assert_eq!(entries, vec![PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde")]);
+        assert_eq!(rejected, vec![(2, String::from("1-3 b: cdefg"))]);
+    }
+
+    #[test]
+    fn test_parse_database_mixed_range_separators() {
+        let lines = vec!["1-3 a: abcde", "1:3 b: cdefg", "2:9 c: ccccccccc", "2-9 c:ccccccccc"];
+        let (entries, rejected) = parse_database(lines.into_iter().map(String::from));
+
+        assert!(rejected.is_empty());
+        assert_eq!(entries[2], entries[3]);
+        assert_eq!(summarize(&entries, SledRental), ValidationSummary { total: 4, valid: 3, invalid: 1 });
+    }
+}
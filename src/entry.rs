@@ -0,0 +1,310 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+
+use crate::{PasswordPolicy, PasswordPolicyMode};
+
+/// Entries are ordered by policy, then by password.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PasswordEntry {
+    pub(crate) policy: PasswordPolicy,
+    pub(crate) password: String,
+}
+
+impl PasswordEntry {
+    pub fn new(policy: PasswordPolicy, password: &str) -> PasswordEntry {
+        PasswordEntry {
+            policy,
+            password: String::from(password),
+        }
+    }
+
+    pub fn policy(&self) -> &PasswordPolicy {
+        &self.policy
+    }
+
+    pub fn password(&self) -> &str {
+        self.password.as_str()
+    }
+
+    /// Parses a CSV record of the form `min,max,char,password`. Fields may be enclosed in double
+    /// quotes to contain commas, with `""` standing for a literal quote. Every field is trimmed,
+    /// the password included, as in the native format.
+    pub fn parse_csv(s: &str) -> Option<PasswordEntry> {
+        let fields = split_csv_record(s)?;
+        let (first, second, pattern, password) = match fields.as_slice() {
+            [first, second, pattern, password] => (first, second, pattern, password),
+            _ => return None,
+        };
+
+        let first = PasswordPolicy::parse_bound(first.trim()).ok()?;
+        let second = PasswordPolicy::parse_bound(second.trim()).ok()?;
+        let pattern = PasswordPolicy::parse_pattern(pattern.trim()).ok()?;
+        let policy = PasswordPolicy::try_new(pattern, first, second).ok()?;
+
+        Some(PasswordEntry::new(policy, password.trim()))
+    }
+
+    pub fn is_valid(&self, mode: PasswordPolicyMode) -> bool {
+        self.policy.validate(mode, self.password.as_str())
+    }
+
+    /// Like `is_valid`, but the pattern matches the password's characters regardless of case.
+    pub fn is_valid_ignore_case(&self, mode: PasswordPolicyMode) -> bool {
+        self.policy.validate_ignore_case(mode, self.password.as_str())
+    }
+
+    /// Tells whether the entry's policy can never be satisfied under `mode`.
+    pub fn is_degenerate(&self, mode: PasswordPolicyMode) -> bool {
+        self.policy.is_degenerate(mode)
+    }
+
+    /// Explains why the entry is invalid under `mode`, or returns `None` if it is valid.
+    pub fn failure_reason(&self, mode: PasswordPolicyMode) -> Option<String> {
+        self.policy.failure_reason(mode, self.password.as_str())
+    }
+
+    /// Like `is_valid`, but also explains the outcome, e.g. `'a' appears 2 times, allowed 1..=3`.
+    pub fn validate_explained(&self, mode: PasswordPolicyMode) -> ValidationResult {
+        ValidationResult {
+            valid: self.is_valid(mode),
+            mode,
+            detail: self.policy.explain(mode, self.password.as_str()),
+        }
+    }
+
+    /// Indexes the password once, so that repeated validations do not scan it again.
+    pub fn prepare(self) -> PreparedEntry {
+        let chars: Vec<char> = self.password.chars().collect();
+        let occurrences = self.policy.occurrences(self.password.as_str());
+
+        PreparedEntry {
+            entry: self,
+            chars,
+            occurrences,
+        }
+    }
+}
+
+/// The outcome of `PasswordEntry::validate_explained`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub mode: PasswordPolicyMode,
+    /// What the validation looked at, in words.
+    pub detail: String,
+}
+
+/// A `PasswordEntry` with its password indexed by `PasswordEntry::prepare`. Validations take
+/// constant time, at the cost of storing every character of the password.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreparedEntry {
+    entry: PasswordEntry,
+    chars: Vec<char>,
+    occurrences: u32,
+}
+
+impl PreparedEntry {
+    /// Gives the same result as `PasswordEntry::is_valid`.
+    pub fn is_valid(&self, mode: PasswordPolicyMode) -> bool {
+        let policy = &self.entry.policy;
+        match mode {
+            PasswordPolicyMode::SledRental => {
+                policy.first <= self.occurrences && self.occurrences <= policy.second
+            }
+            PasswordPolicyMode::SledRentalExclusive => {
+                policy.first <= self.occurrences && self.occurrences < policy.second
+            }
+            PasswordPolicyMode::TobogganCorporate => {
+                let matches = |position: u32| {
+                    let index = position.checked_sub(1)? as usize;
+                    self.chars.get(index).map(|&c| c == policy.pattern)
+                };
+                matches(policy.first) != matches(policy.second)
+            }
+            PasswordPolicyMode::TobogganFromEnd => {
+                let matches = |position: u32| {
+                    let index = position.checked_sub(1)? as usize;
+                    let index = self.chars.len().checked_sub(index + 1)?;
+                    self.chars.get(index).map(|&c| c == policy.pattern)
+                };
+                matches(policy.first) != matches(policy.second)
+            }
+            PasswordPolicyMode::ExactCount => self.occurrences == policy.first,
+            PasswordPolicyMode::Both => {
+                self.is_valid(PasswordPolicyMode::SledRental) && self.is_valid(PasswordPolicyMode::TobogganCorporate)
+            }
+        }
+    }
+
+    pub fn entry(&self) -> &PasswordEntry {
+        &self.entry
+    }
+
+    pub fn into_entry(self) -> PasswordEntry {
+        self.entry
+    }
+}
+
+fn split_csv_record(s: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    // Whether the current field was quoted and its closing quote has been read: only a comma may
+    // follow.
+    let mut closed = false;
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => {
+                quoted = false;
+                closed = true;
+            }
+            ',' if !quoted => {
+                fields.push(mem::take(&mut field));
+                closed = false;
+            }
+            _ if closed => return None,
+            '"' if field.is_empty() => quoted = true,
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return None;
+    }
+    fields.push(field);
+
+    Some(fields)
+}
+
+/// Formats the entry as `<first>-<second> <pattern>: <password>`, which `PasswordEntry::parse`
+/// reads back into an equal entry unless the password starts or ends with whitespace or the
+/// pattern is a colon.
+impl fmt::Display for PasswordEntry {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}: {}", self.policy, self.password)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::PasswordPolicyMode::{Both, ExactCount, SledRental, SledRentalExclusive, TobogganCorporate, TobogganFromEnd};
+
+    use std::collections::HashSet;
+
+    use crate::*;
+
+    #[test]
+    fn test_prepared_entry() {
+        let mut lines = synthetic_lines(500, 11);
+        lines.extend(["0-3 a: abcde", "1-30 a: abcde", "3-3 a: aaa", "1-2 é: éeé"].iter().map(|s| String::from(*s)));
+        let (entries, _) = parse_database(lines.into_iter());
+
+        for entry in entries {
+            let prepared = entry.clone().prepare();
+            for &mode in &[SledRental, SledRentalExclusive, TobogganCorporate, TobogganFromEnd, ExactCount, Both] {
+                assert_eq!(prepared.is_valid(mode), entry.is_valid(mode), "{} in {} mode", entry, mode);
+            }
+            assert_eq!(prepared.into_entry(), entry);
+        }
+    }
+
+    #[test]
+    fn test_password_entry_empty_password() {
+        let entry = PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "");
+        assert_eq!(PasswordEntry::parse("1-3 a: "), Some(entry.clone()));
+        assert_eq!(PasswordEntry::parse("1-3 a:"), Some(entry.clone()));
+        assert_eq!(PasswordEntry::parse("1-3 a:   "), Some(entry.clone()));
+
+        assert_eq!(entry.policy.occurrences(""), 0);
+        for &mode in &[SledRental, SledRentalExclusive, TobogganCorporate, TobogganFromEnd, ExactCount, Both] {
+            assert!(!entry.is_valid(mode), "{} mode", mode);
+        }
+        assert_eq!(entry.failure_reason(SledRental), Some(String::from("count 0 not in 1..=3")));
+        assert_eq!(entry.failure_reason(TobogganCorporate), Some(String::from("neither position 1 nor 3 matches")));
+        assert_eq!(entry.policy.validate_positions(""), Err(PositionError { position: 1, length: 0 }));
+
+        // Only a policy built with `new` can accept no occurrence at all.
+        assert!(PasswordPolicy::new('a', 0, 3).validate(SledRental, ""));
+    }
+
+    #[test]
+    fn test_password_entry_parse_csv() {
+        assert_eq!(PasswordEntry::parse_csv("1,3,a,abcde"), Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde")));
+        assert_eq!(PasswordEntry::parse_csv("2, 9, c, ccccccccc"), Some(PasswordEntry::new(PasswordPolicy::new('c', 2, 9), "ccccccccc")));
+        assert_eq!(PasswordEntry::parse_csv(r#"1,3,a,"ab,cde""#), Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "ab,cde")));
+        assert_eq!(PasswordEntry::parse_csv(r#"1,3,",","a,b""c""#), Some(PasswordEntry::new(PasswordPolicy::new(',', 1, 3), r#"a,b"c"#)));
+
+        assert_eq!(PasswordEntry::parse_csv(""), None);
+        assert_eq!(PasswordEntry::parse_csv("1,3,a"), None);
+        assert_eq!(PasswordEntry::parse_csv("1,3,a,abc,def"), None);
+        assert_eq!(PasswordEntry::parse_csv(r#"1,3,a,"abc"#), None);
+        assert_eq!(PasswordEntry::parse_csv(r#"1,3,a,"ab"c"#), None);
+        assert_eq!(PasswordEntry::parse_csv(r#"1,3,"a"b,abc"#), None);
+        assert_eq!(PasswordEntry::parse_csv("x,3,a,abcde"), None);
+        assert_eq!(PasswordEntry::parse_csv("1,3,ab,abcde"), None);
+    }
+
+    #[test]
+    fn test_password_entry_hash() {
+        let entry = PasswordEntry::parse("1-3 a: abcde").unwrap();
+        let entries = vec![entry.clone(), PasswordEntry::parse("1-3 b: cdefg").unwrap(), entry];
+        let unique: HashSet<PasswordEntry> = entries.into_iter().collect();
+
+        assert_eq!(unique.len(), 2);
+        assert!(unique.contains(&PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde")));
+        assert!(unique.contains(&PasswordEntry::new(PasswordPolicy::new('b', 1, 3), "cdefg")));
+    }
+
+    #[test]
+    fn test_password_entry_display_parse_round_trip() {
+        let entries = vec![
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"),
+            PasswordEntry::new(PasswordPolicy::new('h', 9, 15), "hhhhhhhhh"),
+            PasswordEntry::new(PasswordPolicy::new('é', 1, 2), "éte"),
+            PasswordEntry::new(PasswordPolicy::new('-', 4, 4), "x-y"),
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "with inner  spaces"),
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "colons: too"),
+            PasswordEntry::new(PasswordPolicy::new('a', 1, 3), ""),
+        ];
+
+        for entry in entries {
+            assert_eq!(PasswordEntry::parse(&entry.to_string()), Some(entry));
+        }
+
+        // Surrounding whitespace of the password, and colon patterns, do not survive the trip.
+        let entry = PasswordEntry::new(PasswordPolicy::new('a', 1, 3), " abc ");
+        assert_eq!(PasswordEntry::parse(&entry.to_string()), Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abc")));
+        assert_eq!(PasswordEntry::parse(&PasswordEntry::new(PasswordPolicy::new(':', 1, 2), "a:b").to_string()), None);
+    }
+
+    #[test]
+    fn test_password_entry_validate_explained() {
+        let entry = PasswordEntry::parse("1-3 a: abcde").unwrap();
+        assert_eq!(entry.validate_explained(SledRental), ValidationResult {
+            valid: true,
+            mode: SledRental,
+            detail: String::from("'a' appears 1 times, allowed 1..=3"),
+        });
+        assert_eq!(entry.validate_explained(TobogganCorporate).detail, "positions 1,3 → 'a','c' → exactly-one=true");
+
+        let result = PasswordEntry::parse("2-9 c: ccccccccc").unwrap().validate_explained(TobogganCorporate);
+        assert!(!result.valid);
+        assert_eq!(result.detail, "positions 2,9 → 'c','c' → exactly-one=false");
+
+        let entry = PasswordEntry::parse("1-9 b: cdbfg").unwrap();
+        let result = entry.validate_explained(TobogganCorporate);
+        assert!(result.valid);
+        assert_eq!(result.detail, "positions 1,9 → 'c', pos 9 out of range");
+        assert_eq!(entry.validate_explained(TobogganFromEnd).detail, "positions 1,9 from the end → 'g', pos 9 out of range");
+        assert_eq!(entry.validate_explained(ExactCount).detail, "'b' appears 1 times, required 1");
+        assert_eq!(entry.validate_explained(Both).detail, "'b' appears 1 times, allowed 1..=9; positions 1,9 → 'c', pos 9 out of range");
+    }
+}
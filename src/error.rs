@@ -0,0 +1,175 @@
+use core::fmt;
+use core::ops::Range;
+
+use crate::PasswordPolicyMode;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum PasswordPolicyParseError {
+    /// The policy is not of the form `<first>-<second> <pattern>` or `<first>:<second> <pattern>`.
+    MissingSeparator,
+    /// One of the range bounds is not a number.
+    InvalidRange,
+    /// One of the range bounds is zero, or above `max`, the configured maximum or `u32::MAX`.
+    RangeOutOfBounds { max: u32 },
+    /// One of the range bounds is too large to fit in a `u32`.
+    NumberOverflow,
+    /// The first bound is greater than the second.
+    ReversedRange,
+    /// The pattern is not a single character, or is whitespace or a control character.
+    BadPattern,
+}
+
+impl fmt::Display for PasswordPolicyParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PasswordPolicyParseError::MissingSeparator => {
+                write!(fmt, "expected a policy of the form `<first>-<second> <pattern>` or `<first>:<second> <pattern>`")
+            }
+            PasswordPolicyParseError::InvalidRange => write!(fmt, "range bounds must be numbers"),
+            PasswordPolicyParseError::RangeOutOfBounds { max } => {
+                write!(fmt, "range bounds must be between 1 and {}", max)
+            }
+            PasswordPolicyParseError::NumberOverflow => {
+                write!(fmt, "range bounds must not exceed {}", u32::MAX)
+            }
+            PasswordPolicyParseError::ReversedRange => {
+                write!(fmt, "first bound must not be greater than the second")
+            }
+            PasswordPolicyParseError::BadPattern => {
+                write!(fmt, "pattern must be a single printable, non-whitespace character")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PasswordPolicyParseError {}
+
+/// An entry could not be parsed.
+#[derive(Debug, Eq, PartialEq)]
+pub enum EntryParseError {
+    /// The policy of the entry is malformed.
+    Policy(PasswordPolicyParseError),
+    /// The entry has no separator between the policy and the password, e.g. `1-3 a abcde`.
+    MissingSeparator,
+    /// A policy line is not followed by a password line, in the two-line format.
+    MissingPassword,
+}
+
+impl From<PasswordPolicyParseError> for EntryParseError {
+    fn from(err: PasswordPolicyParseError) -> EntryParseError {
+        EntryParseError::Policy(err)
+    }
+}
+
+impl fmt::Display for EntryParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntryParseError::Policy(err) => write!(fmt, "invalid policy: {}", err),
+            EntryParseError::MissingSeparator => write!(fmt, "missing separator between the policy and the password"),
+            EntryParseError::MissingPassword => write!(fmt, "policy without a password line"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EntryParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EntryParseError::Policy(err) => Some(err),
+            EntryParseError::MissingSeparator | EntryParseError::MissingPassword => None,
+        }
+    }
+}
+
+/// A `PasswordPolicyParseError` together with the part of the input it was raised for.
+#[derive(Debug, Eq, PartialEq)]
+pub struct SpannedParseError {
+    pub error: PasswordPolicyParseError,
+    /// The byte range of the offending token in the parsed input. Separator errors span the whole
+    /// input, and reversed ranges span both bounds.
+    pub span: Range<usize>,
+}
+
+impl SpannedParseError {
+    /// Returns the offending token of `input`, which must be the string that was parsed.
+    pub fn fragment<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.span.clone()]
+    }
+}
+
+impl fmt::Display for SpannedParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{} (at bytes {}..{})", self.error, self.span.start, self.span.end)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SpannedParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A policy position does not designate a character of the password.
+#[derive(Debug, Eq, PartialEq)]
+pub struct PositionError {
+    /// The offending 1-indexed position.
+    pub position: u32,
+    /// The length of the password, in characters.
+    pub length: usize,
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "position {} is out of range for a password of {} characters", self.position, self.length)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PositionError {}
+
+/// A database does not have the expected number of valid entries.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CountMismatch {
+    pub mode: PasswordPolicyMode,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl fmt::Display for CountMismatch {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "expected {} valid passwords in {} mode, found {}", self.expected, self.mode, self.actual)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CountMismatch {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_entry_parse_error_wraps_policy_error() {
+        use std::error::Error;
+
+        let err = PasswordEntry::try_parse("3-1 a: abc").unwrap_err();
+        assert_eq!(err, EntryParseError::Policy(PasswordPolicyParseError::ReversedRange));
+        assert_eq!(err.to_string(), "invalid policy: first bound must not be greater than the second");
+        assert!(err.source().is_some());
+
+        assert!(matches!(PasswordEntry::try_parse("1-3 é ab: é"), Err(EntryParseError::Policy(_))));
+        assert_eq!(EntryParseError::from(PasswordPolicyParseError::BadPattern), EntryParseError::Policy(PasswordPolicyParseError::BadPattern));
+    }
+
+    #[test]
+    fn test_password_policy_parse_error_to_string() {
+        assert_eq!(
+            PasswordPolicyParseError::MissingSeparator.to_string(),
+            "expected a policy of the form `<first>-<second> <pattern>` or `<first>:<second> <pattern>`"
+        );
+        assert_eq!(PasswordPolicyParseError::RangeOutOfBounds { max: 20 }.to_string(), "range bounds must be between 1 and 20");
+        assert_eq!(PasswordPolicyParseError::BadPattern.to_string(), "pattern must be a single printable, non-whitespace character");
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use database::*;
+pub use entry::{PasswordEntry, PreparedEntry, ValidationResult};
+pub use error::{CountMismatch, EntryParseError, PasswordPolicyParseError, PositionError, SpannedParseError};
+pub use parse::PolicyParseOptions;
+pub use policy::{PasswordPolicy, PasswordPolicyMode, PasswordPolicyModeParseError, SubstringPolicy};
+
+mod database;
+mod entry;
+mod error;
+mod parse;
+mod policy;
+
+/// The example database of the puzzle: two of its passwords are valid under `SledRental` rules,
+/// one under `TobogganCorporate` rules.
+#[cfg(any(test, feature = "test-util"))]
+pub const SAMPLE_INPUT: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::PasswordPolicyMode::{SledRental, TobogganCorporate};
+
+    use super::*;
+
+    #[test]
+    fn test_sample_input() {
+        let (entries, rejected) = parse_database(SAMPLE_INPUT.lines().map(String::from));
+
+        assert_eq!(entries.len(), 3);
+        assert!(rejected.is_empty());
+        assert_eq!(validate_all(&entries, SledRental), vec![true, false, true]);
+        assert_eq!(validate_all(&entries, TobogganCorporate), vec![true, false, false]);
+        assert_eq!(count_both(&entries), (2, 1));
+    }
+}
@@ -1,150 +1,585 @@
-use core::fmt;
-use std::cmp::Eq;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use std::fmt;
 use std::fs::File;
 use std::io;
-use std::io::BufRead;
+use std::io::{BufRead, BufWriter, IsTerminal, Read, Write};
 use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
 
-use regex::Regex;
+use advent_of_code_2020_day_2::{database_fingerprint, distinct_char_count, lines_lossy, pattern_histogram, run_repl, strip_comment, summarize, summarize_ignore_case, PasswordEntry, PasswordPolicyMode, ValidationSummary};
 
-fn main() {
-    let database: Vec<PasswordEntry> = read_lines("./input")
-        .unwrap()
-        .filter_map(|line| line.ok())
-        .filter_map(|str| PasswordEntry::parse(str.as_str()))
-        .collect();
+#[cfg(feature = "config")]
+use crate::config::{Config, CONFIG_FILE};
 
-    let total = database.len();
-    let valid = database.iter()
-        .filter(|e| e.is_valid(PasswordPolicyMode::TobogganCorporate))
-        .count();
-    let invalid = total - valid;
+#[cfg(feature = "config")]
+mod config;
+
+const DEFAULT_INPUT: &str = "./input";
 
-    println!("There are {} / {} valid passwords ({} invalid passwords)", valid, total, invalid);
+enum OutputFormat {
+    Text,
+    Json,
+    /// Only the number of valid passwords, as set by `--count-only`.
+    Count,
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-    where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+enum InputFormat {
+    Native,
+    Csv,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct PasswordEntry {
-    policy: PasswordPolicy,
-    password: String,
+struct Options {
+    /// The input files, `-` standing for stdin. Empty if no path was given.
+    paths: Vec<String>,
+    mode: PasswordPolicyMode,
+    format: OutputFormat,
+    input_format: InputFormat,
+    /// The character separating the policy from the password in the native format.
+    delimiter: char,
+    verbose: bool,
+    /// Whether to only parse the input and report what would be validated.
+    dry_run: bool,
+    /// Whether the pattern matches characters regardless of their case.
+    ignore_case: bool,
+    /// Whether to report on stderr how much of each input file has been read.
+    progress: bool,
+    /// Whether to print how many policies use each pattern character.
+    stats: bool,
+    /// Whether to print the fingerprint of each database.
+    fingerprint: bool,
+    /// Whether to print on stderr how long reading and validating each database takes.
+    time: bool,
+    /// Whether to fail when any password is invalid.
+    strict: bool,
+    /// The only pattern characters to consider, as set by `--only-patterns`; all if `None`.
+    only_patterns: Option<HashSet<char>>,
+    /// The pattern characters to leave out, as set by `--except-patterns`; none if `None`.
+    except_patterns: Option<HashSet<char>>,
+    /// The number of unparseable lines at which to give up on a file, as set by `--max-errors`;
+    /// unlimited if `None`.
+    max_errors: Option<usize>,
+    /// The number of entries to read from each file, as set by `--sample`; all of them if `None`.
+    sample: Option<usize>,
 }
 
-impl PasswordEntry {
-    fn new(policy: PasswordPolicy, password: &str) -> PasswordEntry {
-        PasswordEntry {
-            policy,
-            password: String::from(password),
+/// An error that stops the program, along with the context needed to report it.
+#[derive(Debug)]
+enum AppError {
+    /// The command-line arguments or the configuration file are invalid.
+    Usage(String),
+    /// The input at `path` cannot be read.
+    Read { path: String, source: io::Error },
+    /// The input at `path` has `count` unparseable lines, the last one at `line_number`,
+    /// reaching the `--max-errors` limit.
+    TooManyErrors { path: String, line_number: usize, count: usize },
+    /// The REPL cannot read its input or write its output.
+    Repl(io::Error),
+    /// The report cannot be written to stdout.
+    Output(io::Error),
+}
+
+impl AppError {
+    /// Usage errors exit with 2, like other command-line tools; other errors exit with 1.
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            AppError::Usage(_) => ExitCode::from(2),
+            AppError::Read { .. } | AppError::TooManyErrors { .. } | AppError::Repl(_) | AppError::Output(_) => {
+                ExitCode::FAILURE
+            }
         }
     }
+}
 
-    fn parse(s: &str) -> Option<PasswordEntry> {
-        let parts: Vec<&str> = s.splitn(2, ":").collect();
-        let policy = parts.get(0).map(|s| s.trim()).and_then(PasswordPolicy::parse)?;
-        let password = parts.get(1).map(|s| s.trim())?;
+impl fmt::Display for AppError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::Usage(message) => write!(fmt, "{}", message),
+            AppError::Read { path, source } => write!(fmt, "cannot read {}: {}", path, source),
+            AppError::TooManyErrors { path, line_number, count } => {
+                write!(fmt, "{}: stopped at line {} after {} unparseable lines", path, line_number, count)
+            }
+            AppError::Repl(err) => write!(fmt, "{}", err),
+            AppError::Output(err) => write!(fmt, "cannot write output: {}", err),
+        }
+    }
+}
 
-        Some(PasswordEntry::new(policy, password))
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppError::Usage(_) | AppError::TooManyErrors { .. } => None,
+            AppError::Read { source, .. } => Some(source),
+            AppError::Repl(err) | AppError::Output(err) => Some(err),
+        }
     }
+}
 
-    fn is_valid(&self, mode: PasswordPolicyMode) -> bool {
-        self.policy.validate(mode, self.password.as_str())
+// `main` reports errors itself rather than returning them, so that usage errors and `--strict`
+// failures can exit with their own codes.
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            err.exit_code()
+        }
     }
 }
 
-impl fmt::Display for PasswordEntry {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}: {}", self.policy, self.password)
+fn run() -> Result<ExitCode, AppError> {
+    let mut args = env::args().skip(1).peekable();
+    let repl = args.next_if(|arg| arg == "repl").is_some();
+    let options = load_config().and_then(|config| parse_args(args, config)).map_err(AppError::Usage)?;
+
+    if repl {
+        let parse = |line: &str| PasswordEntry::try_parse_with_delimiter(line, options.delimiter);
+        run_repl(io::stdin().lock(), io::stdout().lock(), parse, |entry| entry_is_valid(entry, &options))
+            .map_err(AppError::Repl)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let paths = match options.paths.as_slice() {
+        [] if !io::stdin().is_terminal() => vec![String::from("-")],
+        [] => vec![String::from(DEFAULT_INPUT)],
+        paths => paths.to_vec(),
+    };
+
+    if options.dry_run {
+        for path in &paths {
+            let (database, skipped) = read_database(path, &options)?;
+            println!("{}: {} entries parsed, {} lines skipped; would validate in {} mode",
+                     path, database.len(), skipped, options.mode);
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // With several files, each gets a labelled summary line followed by the grand total. JSON and
+    // count outputs only print the grand total.
+    let labelled = paths.len() > 1;
+    let mut total = ValidationSummary::default();
+    for path in &paths {
+        let summary = process(path, &options)?;
+        if labelled && matches!(options.format, OutputFormat::Text) {
+            println!("{}: {}", path, summary);
+        }
+        total += summary;
+    }
+
+    match options.format {
+        OutputFormat::Text if labelled => println!("Total: {}", total),
+        OutputFormat::Text => println!("{}", total),
+        OutputFormat::Json => println!("{}", total.to_json()),
+        OutputFormat::Count => println!("{}", total.valid),
+    }
+
+    if options.strict && total.invalid > 0 {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct PasswordPolicy {
-    pattern: char,
-    first: u32,
-    second: u32,
+/// Parses the database at `path`, warning about the lines that cannot be parsed. Returns the
+/// entries along with the number of skipped lines. With `--sample`, reading stops as soon as
+/// enough entries have been parsed; with `--max-errors`, it fails as soon as too many lines have
+/// been skipped.
+fn read_database(path: &str, options: &Options) -> Result<(Vec<PasswordEntry>, usize), AppError> {
+    let reader = open_input(path, options.progress).map_err(|source| AppError::Read { path: String::from(path), source })?;
+    let parse = |line: &str| match options.input_format {
+        InputFormat::Native => PasswordEntry::parse_with_delimiter(line, options.delimiter),
+        InputFormat::Csv => PasswordEntry::parse_csv(line),
+    };
+
+    let mut database = Vec::new();
+    let mut rejected = Vec::new();
+    let mut lines = lines_lossy(reader).enumerate();
+    while database.len() < options.sample.unwrap_or(usize::MAX) {
+        let (index, line) = match lines.next() {
+            Some((index, line)) => (index, line.map_err(|source| AppError::Read { path: String::from(path), source })?),
+            None => break,
+        };
+
+        // Lines that are not valid UTF-8 cannot be parsed. Blank and comment-only lines are not
+        // entries, and not failures either. CSV fields may quote a `#`, so CSV has no comments.
+        let entry = match line {
+            Ok(line) => {
+                let line = match options.input_format {
+                    InputFormat::Native => strip_comment(line.as_str()),
+                    InputFormat::Csv => Cow::Borrowed(line.trim()),
+                };
+                if line.is_empty() {
+                    continue;
+                }
+                parse(&line)
+            }
+            Err(_) => None,
+        };
+
+        match entry {
+            Some(entry) => database.push(entry),
+            None => {
+                rejected.push(index + 1);
+                if options.max_errors.is_some_and(|max| rejected.len() >= max) {
+                    return Err(AppError::TooManyErrors { path: String::from(path), line_number: index + 1, count: rejected.len() });
+                }
+            }
+        }
+    }
+
+    if !rejected.is_empty() {
+        let line_numbers: Vec<String> = rejected.iter()
+            .map(|line_number| line_number.to_string())
+            .collect();
+        eprintln!("Warning: {}: skipped {} unparseable lines: {}", path, rejected.len(), line_numbers.join(", "));
+    }
+
+    Ok((database, rejected.len()))
 }
 
-enum PasswordPolicyMode {
-    SledRental,
-    TobogganCorporate,
+/// Parses and validates the database at `path`, printing warnings and verbose output along the
+/// way.
+fn process(path: &str, options: &Options) -> Result<ValidationSummary, AppError> {
+    let start = Instant::now();
+    let (mut database, _) = read_database(path, options)?;
+    if options.time {
+        eprintln!("Time reading and parsing {}: {:?}", path, start.elapsed());
+    }
+    if let Some(allow) = &options.only_patterns {
+        database.retain(|e| allow.contains(&e.policy().pattern()));
+    }
+    if let Some(deny) = &options.except_patterns {
+        database.retain(|e| !deny.contains(&e.policy().pattern()));
+    }
+    let is_valid = |entry: &PasswordEntry| entry_is_valid(entry, options);
+
+    let degenerate = database.iter()
+        .filter(|e| e.is_degenerate(options.mode))
+        .count();
+    if degenerate > 0 {
+        eprintln!("Warning: {}: {} policies can never be satisfied in {} mode", path, degenerate, options.mode);
+    }
+
+    // A single buffered lock on stdout, rather than one lock per line as with `println!`.
+    let mut out = BufWriter::new(io::stdout().lock());
+    if options.verbose && !matches!(options.format, OutputFormat::Count) {
+        write_verbose(&mut out, &database, is_valid, use_color()).map_err(AppError::Output)?;
+    }
+    if options.stats && !matches!(options.format, OutputFormat::Count) {
+        write_stats(&mut out, &database).map_err(AppError::Output)?;
+    }
+    if options.fingerprint && !matches!(options.format, OutputFormat::Count) {
+        writeln!(out, "Fingerprint of {}: {:016x}", path, database_fingerprint(&database)).map_err(AppError::Output)?;
+    }
+    out.flush().map_err(AppError::Output)?;
+
+    let start = Instant::now();
+    let summary = if options.ignore_case {
+        summarize_ignore_case(&database, options.mode)
+    } else {
+        summarize(&database, options.mode)
+    };
+    if options.time {
+        eprintln!("Time validating {}: {:?}", path, start.elapsed());
+    }
+
+    Ok(summary)
 }
 
-impl PasswordPolicy {
-    fn new(pattern: char, first: u32, second: u32) -> PasswordPolicy {
-        PasswordPolicy {
-            pattern,
-            first,
-            second,
-        }
+/// Validates `entry` under the mode of `options`, ignoring case with `--ignore-case`.
+fn entry_is_valid(entry: &PasswordEntry, options: &Options) -> bool {
+    if options.ignore_case {
+        entry.is_valid_ignore_case(options.mode)
+    } else {
+        entry.is_valid(options.mode)
     }
+}
 
-    fn parse(s: &str) -> Option<PasswordPolicy> {
-        let regex = Regex::new(r"^(\d{1,3})-(\d{1,3})\s([a-z])$").unwrap();
-        let capture = regex.captures(s)?;
+/// Writes each entry followed by its validity, in green or red with `color`.
+fn write_verbose<F>(out: &mut impl Write, database: &[PasswordEntry], is_valid: F, color: bool) -> io::Result<()>
+    where F: Fn(&PasswordEntry) -> bool, {
+    for entry in database {
+        let validity = match (is_valid(entry), color) {
+            (true, true) => "\x1b[32mVALID\x1b[0m",
+            (true, false) => "VALID",
+            (false, true) => "\x1b[31mINVALID\x1b[0m",
+            (false, false) => "INVALID",
+        };
+        writeln!(out, "{} -> {}", entry, validity)?;
+    }
 
-        let first = capture.get(1)?.as_str().parse().ok()?;
-        let second = capture.get(2)?.as_str().parse().ok()?;
-        let pattern = capture.get(3)?.as_str();
+    Ok(())
+}
 
-        Some(PasswordPolicy::new(pattern.chars().next()?, first, second))
+/// Writes how many policies use each pattern, most frequent first, and the average number of
+/// distinct characters per password.
+fn write_stats(out: &mut impl Write, database: &[PasswordEntry]) -> io::Result<()> {
+    let mut histogram: Vec<(char, usize)> = pattern_histogram(database).into_iter().collect();
+    histogram.sort_by(|(_, a), (_, b)| b.cmp(a));
+    writeln!(out, "Pattern frequencies:")?;
+    for (pattern, count) in histogram {
+        writeln!(out, "  {}: {}", pattern, count)?;
     }
 
-    fn validate(&self, mode: PasswordPolicyMode, s: &str) -> bool {
-        match mode {
-            PasswordPolicyMode::SledRental => {
-                let count = s.matches(self.pattern).count() as u32;
-                self.first <= count && count <= self.second
+    let distinct: usize = database.iter()
+        .map(|e| distinct_char_count(e.password()))
+        .sum();
+    writeln!(out, "Average distinct characters per password: {:.2}", distinct as f64 / database.len().max(1) as f64)
+}
+
+#[cfg(feature = "config")]
+fn load_config() -> Result<Config, String> {
+    Config::load(CONFIG_FILE)
+}
+
+#[cfg(not(feature = "config"))]
+fn load_config() -> Result<Config, String> {
+    Ok(Config::default())
+}
+
+/// Without the `config` feature, there are no defaults to read.
+#[cfg(not(feature = "config"))]
+#[derive(Default)]
+struct Config {
+    mode: Option<String>,
+    input: Option<String>,
+    format: Option<String>,
+}
+
+/// Tells whether to color the output: only when stdout is a terminal, and unless the `NO_COLOR`
+/// environment variable is set to a non-empty value (see https://no-color.org).
+fn use_color() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// Parses the command-line arguments, falling back to `config` for the options they do not set.
+fn parse_args(mut args: impl Iterator<Item = String>, config: Config) -> Result<Options, String> {
+    let mut paths = Vec::new();
+    let mut mode = match config.mode {
+        Some(mode) => mode.parse::<PasswordPolicyMode>().map_err(|err| err.to_string())?,
+        None => PasswordPolicyMode::TobogganCorporate,
+    };
+    let mut format = match config.format {
+        Some(format) => parse_format(format.as_str())?,
+        None => OutputFormat::Text,
+    };
+    let mut input_format = InputFormat::Native;
+    let mut delimiter = ':';
+    let mut verbose = false;
+    let mut dry_run = false;
+    let mut ignore_case = false;
+    let mut progress = false;
+    let mut stats = false;
+    let mut fingerprint = false;
+    let mut time = false;
+    let mut strict = false;
+    let mut sample = None;
+    let mut max_errors = None;
+    let mut only_patterns = None;
+    let mut except_patterns = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--mode" => {
+                let value = args.next().ok_or("missing value for --mode")?;
+                mode = value.parse::<PasswordPolicyMode>().map_err(|err| err.to_string())?;
+            }
+            "--format" => {
+                let value = args.next().ok_or("missing value for --format")?;
+                format = parse_format(value.as_str())?;
+            }
+            "--input-format" => {
+                let value = args.next().ok_or("missing value for --input-format")?;
+                input_format = parse_input_format(value.as_str())?;
+            }
+            "--delimiter" => {
+                let value = args.next().ok_or("missing value for --delimiter")?;
+                delimiter = parse_delimiter(value.as_str())?;
             }
-            PasswordPolicyMode::TobogganCorporate => {
-                let first_char = s.chars().nth((self.first - 1) as usize);
-                let second_char = s.chars().nth((self.second - 1) as usize);
+            "--sample" => {
+                let value = args.next().ok_or("missing value for --sample")?;
+                let count = value.parse::<usize>().map_err(|_| format!("invalid sample size `{}`", value))?;
+                sample = Some(count);
+            }
+            "--max-errors" => {
+                let value = args.next().ok_or("missing value for --max-errors")?;
+                let count = value.parse::<usize>().map_err(|_| format!("invalid error count `{}`", value))?;
+                max_errors = Some(count);
+            }
+            "--only-patterns" => {
+                let value = args.next().ok_or("missing value for --only-patterns")?;
+                only_patterns = Some(value.chars().collect());
+            }
+            "--except-patterns" => {
+                let value = args.next().ok_or("missing value for --except-patterns")?;
+                except_patterns = Some(value.chars().collect());
+            }
+            "--count-only" => format = OutputFormat::Count,
+            "--verbose" => verbose = true,
+            "--dry-run" => dry_run = true,
+            "--ignore-case" => ignore_case = true,
+            "--progress" => progress = true,
+            "--stats" => stats = true,
+            "--fingerprint" => fingerprint = true,
+            "--time" => time = true,
+            "--strict" => strict = true,
+            _ if arg.starts_with("--") => return Err(format!("unexpected argument `{}`", arg)),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        paths.extend(config.input);
+    }
+
+    Ok(Options {
+        paths,
+        mode,
+        format,
+        input_format,
+        delimiter,
+        verbose,
+        dry_run,
+        ignore_case,
+        progress,
+        stats,
+        fingerprint,
+        time,
+        strict,
+        sample,
+        max_errors,
+        only_patterns,
+        except_patterns,
+    })
+}
+
+fn parse_format(s: &str) -> Result<OutputFormat, String> {
+    match s {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        _ => Err(format!("unknown format `{}` (expected `text` or `json`)", s)),
+    }
+}
+
+fn parse_input_format(s: &str) -> Result<InputFormat, String> {
+    match s {
+        "native" => Ok(InputFormat::Native),
+        "csv" => Ok(InputFormat::Csv),
+        _ => Err(format!("unknown input format `{}` (expected `native` or `csv`)", s)),
+    }
+}
+
+fn parse_delimiter(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some('#'), None) => Err(String::from("delimiter `#` would start a comment")),
+        (Some(c), None) => Ok(c),
+        _ => Err(format!("delimiter `{}` must be a single character", s)),
+    }
+}
+
+/// Opens `path` for reading, or stdin if `path` is `-`. With `progress`, reading a file reports
+/// its progress on stderr; stdin has no known length and never does.
+fn open_input<P>(path: P, progress: bool) -> io::Result<Box<dyn BufRead>>
+    where P: AsRef<Path>, {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+
+    let file = File::open(path)?;
+    if progress {
+        let total = file.metadata()?.len();
+        return Ok(Box::new(Progress::new(io::BufReader::new(file), total)));
+    }
+    Ok(Box::new(io::BufReader::new(file)))
+}
 
-                first_char.map(|c| c == self.pattern) != second_char.map(|c| c == self.pattern)
+/// Wraps a reader to print, on stderr, the percentage of its `total` bytes consumed so far each
+/// time it increases.
+struct Progress<R> {
+    inner: R,
+    total: u64,
+    consumed: u64,
+    reported: u64,
+}
+
+impl<R> Progress<R> {
+    fn new(inner: R, total: u64) -> Progress<R> {
+        Progress {
+            inner,
+            total,
+            consumed: 0,
+            reported: 0,
+        }
+    }
+
+    fn advance(&mut self, amount: usize) {
+        if self.total == 0 {
+            return;
+        }
+
+        self.consumed += amount as u64;
+        let percent = self.consumed * 100 / self.total;
+        if percent > self.reported {
+            self.reported = percent;
+            eprint!("\rProgress: {}%", percent);
+            if percent >= 100 {
+                eprintln!();
             }
         }
     }
 }
 
-impl fmt::Display for PasswordPolicy {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}-{} {}", self.first, self.second, self.pattern)
+impl<R: Read> Read for Progress<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let amount = self.inner.read(buf)?;
+        self.advance(amount);
+        Ok(amount)
+    }
+}
+
+impl<R: BufRead> BufRead for Progress<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        self.advance(amount);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::PasswordPolicyMode::{SledRental, TobogganCorporate};
+    use advent_of_code_2020_day_2::{parse_database, PasswordEntry, PasswordPolicyMode};
 
-    use super::*;
+    use super::{write_stats, write_verbose};
 
-    #[test]
-    fn test_password_policy_to_string() {
-        assert_eq!(PasswordPolicy::new('h', 9, 15).to_string(), "9-15 h");
+    fn sample() -> Vec<PasswordEntry> {
+        let lines = vec!["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc"];
+        parse_database(lines.into_iter().map(String::from)).0
     }
 
     #[test]
-    fn test_password_policy_parse() {
-        assert_eq!(PasswordPolicy::parse(""), None);
-        assert_eq!(PasswordPolicy::parse("1- a"), None);
-        assert_eq!(PasswordPolicy::parse("abc"), None);
-        assert_eq!(PasswordPolicy::parse("1-3 a"), Some(PasswordPolicy::new('a', 1, 3)));
-        assert_eq!(PasswordPolicy::parse("1-3 b"), Some(PasswordPolicy::new('b', 1, 3)));
-        assert_eq!(PasswordPolicy::parse("2-9 c"), Some(PasswordPolicy::new('c', 2, 9)));
+    fn test_write_verbose() {
+        let is_valid = |e: &PasswordEntry| e.is_valid(PasswordPolicyMode::SledRental);
+
+        let mut out = Vec::new();
+        write_verbose(&mut out, &sample(), is_valid, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "1-3 a: abcde -> VALID\n1-3 b: cdefg -> INVALID\n2-9 c: ccccccccc -> VALID\n");
+
+        let mut out = Vec::new();
+        write_verbose(&mut out, &sample()[..2], is_valid, true).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "1-3 a: abcde -> \x1b[32mVALID\x1b[0m\n1-3 b: cdefg -> \x1b[31mINVALID\x1b[0m\n");
     }
 
     #[test]
-    fn test_password_policy_validate() {
-        assert_eq!(PasswordPolicy::parse("1-3 a").unwrap().validate(SledRental, "abcde"), true);
-        assert_eq!(PasswordPolicy::parse("1-3 b").unwrap().validate(SledRental, "cdefg"), false);
-        assert_eq!(PasswordPolicy::parse("2-9 c").unwrap().validate(SledRental, "ccccccccc"), true);
-
-        assert_eq!(PasswordPolicy::parse("1-3 a").unwrap().validate(TobogganCorporate, "abcde"), true);
-        assert_eq!(PasswordPolicy::parse("1-3 b").unwrap().validate(TobogganCorporate, "cdefg"), false);
-        assert_eq!(PasswordPolicy::parse("2-9 c").unwrap().validate(TobogganCorporate, "ccccccccc"), false);
+    fn test_write_stats() {
+        let mut out = Vec::new();
+        write_stats(&mut out, &sample()).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "Pattern frequencies:\n  a: 1\n  b: 1\n  c: 1\nAverage distinct characters per password: 3.67\n");
     }
 }
@@ -0,0 +1,462 @@
+//! Parsing of the textual policy and entry formats.
+//!
+//! Policies are split with `regex` when the `regex` feature is enabled, or by hand otherwise and
+//! with the `lite-parser` feature. Both produce the same results and errors.
+
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::ops::Range;
+use core::str::FromStr;
+#[cfg(all(feature = "regex", not(feature = "lite-parser")))]
+use std::sync::OnceLock;
+
+#[cfg(all(feature = "regex", not(feature = "lite-parser")))]
+use regex::Regex;
+
+use crate::{EntryParseError, PasswordEntry, PasswordPolicy, PasswordPolicyParseError, SpannedParseError, SubstringPolicy};
+
+/// Optional checks applied by `PasswordPolicy::try_parse_with`. All checks are off by default.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PolicyParseOptions {
+    /// Rejects policies with a bound above this value with `RangeOutOfBounds`.
+    pub max_position: Option<u32>,
+}
+
+/// Splits a policy into its raw bounds and pattern, all slices of `s`.
+#[cfg(all(feature = "regex", not(feature = "lite-parser")))]
+fn split_policy(s: &str) -> Result<(&str, &str, &str), PasswordPolicyParseError> {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = REGEX.get_or_init(|| Regex::new(r"^([^-:\s]*)[-:](\S*)\s+(.*)$").unwrap());
+    let capture = regex.captures(s).ok_or(PasswordPolicyParseError::MissingSeparator)?;
+
+    Ok((capture.get(1).unwrap().as_str(), capture.get(2).unwrap().as_str(), capture.get(3).unwrap().as_str()))
+}
+
+/// Splits a policy into its raw bounds and pattern, all slices of `s`, accepting the same inputs
+/// as the regex `^([^-:\s]*)[-:](\S*)\s+(.*)$`.
+#[cfg(any(not(feature = "regex"), feature = "lite-parser"))]
+fn split_policy(s: &str) -> Result<(&str, &str, &str), PasswordPolicyParseError> {
+    let (first, rest) = s.split_once(|c: char| c == '-' || c == ':' || c.is_whitespace())
+        .filter(|(first, _)| s[first.len()..].starts_with(['-', ':']))
+        .ok_or(PasswordPolicyParseError::MissingSeparator)?;
+    let (second, rest) = rest.split_once(char::is_whitespace)
+        .ok_or(PasswordPolicyParseError::MissingSeparator)?;
+    let pattern = rest.trim_start();
+    if pattern.contains('\n') {
+        return Err(PasswordPolicyParseError::MissingSeparator);
+    }
+
+    Ok((first, second, pattern))
+}
+
+/// Returns the byte range of `part` within `s`. `part` must be a slice of `s`.
+fn span_of(s: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - s.as_ptr() as usize;
+    start..start + part.len()
+}
+
+impl PasswordPolicy {
+    pub fn parse(s: &str) -> Option<PasswordPolicy> {
+        PasswordPolicy::try_parse(s).ok()
+    }
+
+    pub fn try_parse(s: &str) -> Result<PasswordPolicy, PasswordPolicyParseError> {
+        PasswordPolicy::try_parse_with(s, &PolicyParseOptions::default())
+    }
+
+    /// Like `try_parse`, with additional checks configured by `options`.
+    pub fn try_parse_with(s: &str, options: &PolicyParseOptions) -> Result<PasswordPolicy, PasswordPolicyParseError> {
+        PasswordPolicy::try_parse_spanned(s, options).map_err(|err| err.error)
+    }
+
+    /// Like `try_parse_with`, but the error also locates the offending part of `s`.
+    pub fn try_parse_spanned(s: &str, options: &PolicyParseOptions) -> Result<PasswordPolicy, SpannedParseError> {
+        let at = |span: Range<usize>| move |error| SpannedParseError { error, span };
+
+        let (first, second, pattern) = split_policy(s).map_err(at(0..s.len()))?;
+        let (first_span, second_span) = (span_of(s, first), span_of(s, second));
+        let first = PasswordPolicy::parse_bound(first).map_err(at(first_span.clone()))?;
+        let second = PasswordPolicy::parse_bound(second).map_err(at(second_span.clone()))?;
+        let pattern = PasswordPolicy::parse_pattern(pattern).map_err(at(span_of(s, pattern)))?;
+
+        if let Some(max) = options.max_position {
+            if first > max {
+                return Err(at(first_span)(PasswordPolicyParseError::RangeOutOfBounds { max }));
+            }
+            if second > max {
+                return Err(at(second_span)(PasswordPolicyParseError::RangeOutOfBounds { max }));
+            }
+        }
+
+        PasswordPolicy::try_new(pattern, first, second).map_err(|error| {
+            let max = options.max_position.unwrap_or(u32::MAX);
+            match error {
+                PasswordPolicyParseError::RangeOutOfBounds { .. } if first == 0 => at(first_span)(PasswordPolicyParseError::RangeOutOfBounds { max }),
+                PasswordPolicyParseError::RangeOutOfBounds { .. } => at(second_span)(PasswordPolicyParseError::RangeOutOfBounds { max }),
+                error => at(first_span.start..second_span.end)(error),
+            }
+        })
+    }
+}
+
+impl FromStr for PasswordPolicy {
+    type Err = PasswordPolicyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PasswordPolicy::try_parse(s)
+    }
+}
+
+impl SubstringPolicy {
+    pub fn parse(s: &str) -> Option<SubstringPolicy> {
+        SubstringPolicy::try_parse(s).ok()
+    }
+
+    pub fn try_parse(s: &str) -> Result<SubstringPolicy, PasswordPolicyParseError> {
+        let (first, second, pattern) = split_policy(s)?;
+        let first = PasswordPolicy::parse_bound(first)?;
+        let second = PasswordPolicy::parse_bound(second)?;
+        SubstringPolicy::try_new(pattern, first, second)
+    }
+}
+
+impl FromStr for SubstringPolicy {
+    type Err = PasswordPolicyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SubstringPolicy::try_parse(s)
+    }
+}
+
+impl PasswordEntry {
+    /// Parses an entry of the form `<first>-<second> <pattern>: <password>`. The range may also
+    /// be written `<first>:<second>`.
+    ///
+    /// Whitespace is flexible: any amount of spaces or tabs (including none) may surround the
+    /// `:` separator, and one or more may separate the range from the pattern. Leading and
+    /// trailing whitespace of the password is dropped, whitespace inside it is kept.
+    ///
+    /// The password may be empty (`1-3 a: `). Such an entry parses, but it has no occurrence
+    /// and no character at any position, so it is only valid in `SledRental` or `ExactCount`
+    /// mode with a minimum of 0, which `try_new` does not accept.
+    pub fn parse(s: &str) -> Option<PasswordEntry> {
+        PasswordEntry::try_parse(s).ok()
+    }
+
+    pub fn try_parse(s: &str) -> Result<PasswordEntry, EntryParseError> {
+        PasswordEntry::try_parse_with_delimiter(s, ':')
+    }
+
+    /// Like `parse`, but the password follows `delimiter` instead of `:`, e.g. `1-3 a | abcde`.
+    pub fn parse_with_delimiter(s: &str, delimiter: char) -> Option<PasswordEntry> {
+        PasswordEntry::try_parse_with_delimiter(s, delimiter).ok()
+    }
+
+    pub fn try_parse_with_delimiter(s: &str, delimiter: char) -> Result<PasswordEntry, EntryParseError> {
+        let s = s.trim_start();
+        if !s.contains(delimiter) {
+            return Err(EntryParseError::MissingSeparator);
+        }
+        // The range may itself use a colon (`1:3 a: abcde`), so the password separator is the
+        // first delimiter after the whitespace that precedes the pattern.
+        let pattern_start = s.find(char::is_whitespace)
+            .map(|range_end| range_end + s[range_end..].chars().next().map_or(0, char::len_utf8));
+        let separator = pattern_start.and_then(|start| s[start..].find(delimiter).map(|separator| start + separator));
+        let separator = match separator {
+            Some(separator) => separator,
+            None => {
+                // Without one, the policy ends at the whitespace after the pattern: a valid policy
+                // is only missing its separator (`1:3 a abcde`), while an invalid one holds the
+                // delimiter (`1-3: abcde`).
+                let policy_end = pattern_start.map_or(s.len(), |start| {
+                    s[start..].find(char::is_whitespace).map_or(s.len(), |end| start + end)
+                });
+                return match PasswordPolicy::try_parse(s[..policy_end].trim()) {
+                    Ok(_) => Err(EntryParseError::MissingSeparator),
+                    Err(err) => Err(EntryParseError::Policy(err)),
+                };
+            }
+        };
+        let (policy, password) = s.split_at(separator);
+
+        let policy = PasswordPolicy::try_parse(policy.trim())?;
+        Ok(PasswordEntry::new(policy, password[delimiter.len_utf8()..].trim()))
+    }
+}
+
+impl FromStr for PasswordEntry {
+    type Err = EntryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PasswordEntry::try_parse(s)
+    }
+}
+
+impl TryFrom<&str> for PasswordEntry {
+    type Error = EntryParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        PasswordEntry::try_parse(s)
+    }
+}
+
+impl TryFrom<String> for PasswordEntry {
+    type Error = EntryParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        PasswordEntry::try_parse(s.as_str())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::PasswordPolicyMode::{TobogganCorporate};
+
+    use crate::*;
+
+    #[test]
+    fn test_password_entry_parse_whitespace() {
+        let expected = Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"));
+
+        assert_eq!(PasswordEntry::parse("1-3 a: abcde"), expected);
+        assert_eq!(PasswordEntry::parse("1-3 a :  abcde"), expected);
+        assert_eq!(PasswordEntry::parse("1-3 a:abcde"), expected);
+        assert_eq!(PasswordEntry::parse("1-3\ta:\tabcde"), expected);
+        assert_eq!(PasswordEntry::parse("1-3 \t a\t:\t abcde\t"), expected);
+        assert_eq!(PasswordEntry::parse("  1-3   a  :  abcde  "), expected);
+        assert_eq!(PasswordEntry::parse("1-3 a: ab cde"), Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "ab cde")));
+        assert_eq!(PasswordEntry::parse("1 -3 a: abcde"), None);
+    }
+
+    #[test]
+    fn test_password_entry_parse_with_delimiter() {
+        let expected = Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"));
+
+        assert_eq!(PasswordEntry::parse_with_delimiter("1-3 a | abcde", '|'), expected);
+        assert_eq!(PasswordEntry::parse_with_delimiter("1-3 a|abcde", '|'), expected);
+        assert_eq!(PasswordEntry::parse_with_delimiter("1:3 a | abcde", '|'), expected);
+        assert_eq!(PasswordEntry::parse_with_delimiter("1-3 a\tabcde", '\t'), expected);
+        assert_eq!(PasswordEntry::parse_with_delimiter("1-3\ta\tabcde", '\t'), expected);
+        assert_eq!(PasswordEntry::parse_with_delimiter("1-3 a → abcde", '→'), expected);
+        assert_eq!(PasswordEntry::parse_with_delimiter("1-3 a: abcde", ':'), expected);
+        assert_eq!(
+            PasswordEntry::parse_with_delimiter("1-3 a | ab|c:de", '|'),
+            Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "ab|c:de"))
+        );
+
+        assert_eq!(PasswordEntry::parse_with_delimiter("1-3 a: abcde", '|'), None);
+        assert_eq!(PasswordEntry::try_parse_with_delimiter("1-3 a abcde", '|'), Err(EntryParseError::MissingSeparator));
+    }
+
+    #[test]
+    fn test_password_entry_parse_colons() {
+        let entry = |password| Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), password));
+
+        assert_eq!(PasswordEntry::parse("1-3 a: ab:cde"), entry("ab:cde"));
+        assert_eq!(PasswordEntry::parse("1-3 a: :abcde"), entry(":abcde"));
+        assert_eq!(PasswordEntry::parse("1-3 a: abcde:"), entry("abcde:"));
+        assert_eq!(PasswordEntry::parse("1-3 a::abcde::"), entry(":abcde::"));
+        assert_eq!(PasswordEntry::parse("1-3 a: : "), entry(":"));
+        // The entry is split on its first colon, so a colon cannot be used as the pattern.
+        assert_eq!(PasswordEntry::parse("1-3 :: abcde"), None);
+    }
+
+    #[test]
+    fn test_password_policy_parse() {
+        assert_eq!(PasswordPolicy::parse(""), None);
+        assert_eq!(PasswordPolicy::parse("1- a"), None);
+        assert_eq!(PasswordPolicy::parse("abc"), None);
+        assert_eq!(PasswordPolicy::parse("1-3 a"), Some(PasswordPolicy::new('a', 1, 3)));
+        assert_eq!(PasswordPolicy::parse("1-3 b"), Some(PasswordPolicy::new('b', 1, 3)));
+        assert_eq!(PasswordPolicy::parse("2-9 c"), Some(PasswordPolicy::new('c', 2, 9)));
+    }
+
+    #[test]
+    fn test_password_policy_try_parse() {
+        use PasswordPolicyParseError::*;
+
+        assert_eq!(PasswordPolicy::try_parse(""), Err(MissingSeparator));
+        assert_eq!(PasswordPolicy::try_parse("abc"), Err(MissingSeparator));
+        assert_eq!(PasswordPolicy::try_parse("1-3a"), Err(MissingSeparator));
+        assert_eq!(PasswordPolicy::try_parse("1- a"), Err(InvalidRange));
+        assert_eq!(PasswordPolicy::try_parse("x-3 a"), Err(InvalidRange));
+        assert_eq!(PasswordPolicy::try_parse("1-4294967296 a"), Err(NumberOverflow));
+        assert_eq!(PasswordPolicy::try_parse("99999999999999999999-1 a"), Err(NumberOverflow));
+        assert_eq!(PasswordEntry::try_parse("1-4294967296 a: abc"), Err(EntryParseError::Policy(NumberOverflow)));
+        assert_eq!(NumberOverflow.to_string(), "range bounds must not exceed 4294967295");
+        assert_eq!(PasswordPolicy::try_parse("0-3 a"), Err(RangeOutOfBounds { max: u32::MAX }));
+        assert_eq!(PasswordPolicy::try_parse("1-0 a"), Err(RangeOutOfBounds { max: u32::MAX }));
+        assert_eq!(PasswordPolicy::try_parse("00-3 a"), Err(RangeOutOfBounds { max: u32::MAX }));
+        assert_eq!(PasswordPolicy::try_parse("1-3 "), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_parse("1-3 ab"), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_parse("1-3 a"), Ok(PasswordPolicy::new('a', 1, 3)));
+        assert_eq!(PasswordPolicy::try_parse("1-3 A"), Ok(PasswordPolicy::new('A', 1, 3)));
+    }
+
+    #[test]
+    fn test_password_policy_try_parse_separators() {
+        use PasswordPolicyParseError::*;
+
+        // These cases are sensitive to how the policy is split, which differs between the regex
+        // and the hand-written parsers (see the `lite-parser` feature).
+        assert_eq!(PasswordPolicy::try_parse("-"), Err(MissingSeparator));
+        assert_eq!(PasswordPolicy::try_parse("1 -3 a"), Err(MissingSeparator));
+        assert_eq!(PasswordPolicy::try_parse("1-3"), Err(MissingSeparator));
+        assert_eq!(PasswordPolicy::try_parse("1-3 a\nb"), Err(MissingSeparator));
+        assert_eq!(PasswordPolicy::try_parse("- a"), Err(InvalidRange));
+        assert_eq!(PasswordPolicy::try_parse("1-2-3 a"), Err(InvalidRange));
+        assert_eq!(PasswordPolicy::try_parse("1-3 \n"), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_parse("1-3 a b"), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_parse("1-3\u{2003}a"), Ok(PasswordPolicy::new('a', 1, 3)));
+    }
+
+    #[test]
+    fn test_password_policy_parse_colon_range() {
+        assert_eq!(PasswordPolicy::parse("1:3 a"), Some(PasswordPolicy::new('a', 1, 3)));
+        assert_eq!(PasswordPolicy::parse("1-3 a"), Some(PasswordPolicy::new('a', 1, 3)));
+        assert_eq!(PasswordPolicy::try_parse("1:3:5 a"), Err(PasswordPolicyParseError::InvalidRange));
+        assert_eq!(PasswordPolicy::try_parse("1-:3 a"), Err(PasswordPolicyParseError::InvalidRange));
+
+        let expected = Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"));
+        assert_eq!(PasswordEntry::parse("1:3 a: abcde"), expected);
+        assert_eq!(PasswordEntry::parse("1:3 a:abcde"), expected);
+        assert_eq!(PasswordEntry::parse(" 1:3\ta : abcde"), expected);
+        assert_eq!(PasswordEntry::parse("1:3 a: ab:cde"), Some(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "ab:cde")));
+        assert_eq!(PasswordEntry::try_parse("1:3 a abcde"), Err(EntryParseError::MissingSeparator));
+    }
+
+    #[test]
+    fn test_password_policy_try_parse_with_max_position() {
+        let options = PolicyParseOptions { max_position: Some(20) };
+
+        assert_eq!(PasswordPolicy::try_parse_with("1-20 a", &options), Ok(PasswordPolicy::new('a', 1, 20)));
+        assert_eq!(PasswordPolicy::try_parse_with("20-20 a", &options), Ok(PasswordPolicy::new('a', 20, 20)));
+        assert_eq!(PasswordPolicy::try_parse_with("1-21 a", &options), Err(PasswordPolicyParseError::RangeOutOfBounds { max: 20 }));
+        assert_eq!(PasswordPolicy::try_parse_with("21-22 a", &options), Err(PasswordPolicyParseError::RangeOutOfBounds { max: 20 }));
+        assert_eq!(PasswordPolicy::try_parse_with("999-999 a", &PolicyParseOptions::default()), Ok(PasswordPolicy::new('a', 999, 999)));
+    }
+
+    #[test]
+    fn test_password_policy_parse_large_positions() {
+        let policy = PasswordPolicy::parse("1000-1005 a").unwrap();
+        assert_eq!(policy, PasswordPolicy::new('a', 1000, 1005));
+        assert_eq!(PasswordPolicy::parse("1-4294967295 a"), Some(PasswordPolicy::new('a', 1, u32::MAX)));
+
+        let mut password = "b".repeat(1010);
+        password.replace_range(999..1000, "a");
+        assert!(policy.validate(TobogganCorporate, &password));
+        password.replace_range(1004..1005, "a");
+        assert!(!policy.validate(TobogganCorporate, &password));
+    }
+
+    #[test]
+    fn test_password_policy_parse_unicode() {
+        assert_eq!(PasswordPolicy::parse("1-3 é"), Some(PasswordPolicy::new('é', 1, 3)));
+        assert_eq!(PasswordPolicy::parse("1-3 α"), Some(PasswordPolicy::new('α', 1, 3)));
+        assert_eq!(PasswordPolicy::parse("2-4 字"), Some(PasswordPolicy::new('字', 2, 4)));
+        assert_eq!(PasswordPolicy::parse("1-3 αβ"), None);
+    }
+
+    #[test]
+    fn test_substring_policy_parse() {
+        use PasswordPolicyParseError::*;
+
+        assert_eq!(SubstringPolicy::parse("1-3 ab"), Some(SubstringPolicy::new("ab", 1, 3)));
+        assert_eq!(SubstringPolicy::parse("1-3 a"), Some(SubstringPolicy::new("a", 1, 3)));
+        assert_eq!(SubstringPolicy::parse("2-4 αβ"), Some(SubstringPolicy::new("αβ", 2, 4)));
+        assert_eq!(SubstringPolicy::try_parse("1-3 "), Err(BadPattern));
+        assert_eq!(SubstringPolicy::try_parse("1-3 a b"), Err(BadPattern));
+        assert_eq!(SubstringPolicy::try_parse("3-1 ab"), Err(ReversedRange));
+        assert_eq!(SubstringPolicy::try_parse("abc"), Err(MissingSeparator));
+        assert_eq!(SubstringPolicy::new("ab", 1, 3).to_string(), "1-3 ab");
+    }
+
+    #[test]
+    fn test_password_policy_from_str() {
+        use PasswordPolicyParseError::*;
+
+        assert_eq!("".parse::<PasswordPolicy>(), Err(MissingSeparator));
+        assert_eq!("1- a".parse::<PasswordPolicy>(), Err(InvalidRange));
+        assert_eq!("abc".parse::<PasswordPolicy>(), Err(MissingSeparator));
+        assert_eq!("1-3 a".parse::<PasswordPolicy>(), Ok(PasswordPolicy::new('a', 1, 3)));
+        assert_eq!("2-9 c".parse::<PasswordPolicy>(), Ok(PasswordPolicy::new('c', 2, 9)));
+    }
+
+    #[test]
+    fn test_password_entry_from_str() {
+        use PasswordPolicyParseError::*;
+
+        assert_eq!("".parse::<PasswordEntry>(), Err(EntryParseError::MissingSeparator));
+        assert_eq!("1-3 a abcde".parse::<PasswordEntry>(), Err(EntryParseError::MissingSeparator));
+        assert_eq!("1- a: abcde".parse::<PasswordEntry>(), Err(EntryParseError::Policy(InvalidRange)));
+        assert_eq!("abc: abcde".parse::<PasswordEntry>(), Err(EntryParseError::Policy(InvalidRange)));
+        assert_eq!("1-3: abcde".parse::<PasswordEntry>(), Err(EntryParseError::Policy(InvalidRange)));
+        assert_eq!("1-3a: abcde".parse::<PasswordEntry>(), Err(EntryParseError::Policy(InvalidRange)));
+        assert_eq!("13 a: abcde".parse::<PasswordEntry>(), Err(EntryParseError::Policy(MissingSeparator)));
+        assert_eq!(EntryParseError::MissingSeparator.to_string(), "missing separator between the policy and the password");
+        assert_eq!(
+            "1-3 a: abcde".parse::<PasswordEntry>(),
+            Ok(PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"))
+        );
+    }
+
+    #[test]
+    fn test_password_entry_try_from() {
+        use std::convert::{TryFrom, TryInto};
+        use PasswordPolicyParseError::*;
+
+        let expected = PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde");
+        assert_eq!(PasswordEntry::try_from("1-3 a: abcde"), Ok(expected.clone()));
+        assert_eq!(PasswordEntry::try_from(String::from("1-3 a: abcde")), Ok(expected.clone()));
+        assert_eq!("1-3 a: abcde".try_into(), Ok(expected));
+
+        assert_eq!(PasswordEntry::try_from("1-3 a abcde"), Err(EntryParseError::MissingSeparator));
+        assert_eq!(PasswordEntry::try_from(String::from("1-x a: abcde")), Err(EntryParseError::Policy(InvalidRange)));
+        for line in &["", "1-3 b: cdefg", "3-1 a: a", "1-3 ab: abc", "abc: abcde"] {
+            assert_eq!(PasswordEntry::try_from(*line), line.parse::<PasswordEntry>());
+        }
+    }
+
+    #[test]
+    fn test_password_policy_try_parse_spanned() {
+        use PasswordPolicyParseError::*;
+
+        let options = PolicyParseOptions::default();
+        let spanned = |s| PasswordPolicy::try_parse_spanned(s, &options).unwrap_err();
+
+        let err = spanned("1-x a");
+        assert_eq!(err, SpannedParseError { error: InvalidRange, span: 2..3 });
+        assert_eq!(err.fragment("1-x a"), "x");
+        assert_eq!(err.to_string(), "range bounds must be numbers (at bytes 2..3)");
+
+        assert_eq!(spanned("y-3 a").span, 0..1);
+        assert_eq!(spanned("12-99999999999 a").fragment("12-99999999999 a"), "99999999999");
+        assert_eq!(spanned("1-3 ab").fragment("1-3 ab"), "ab");
+        assert_eq!(spanned("0-3 a").span, 0..1);
+        assert_eq!(spanned("50-3 a").fragment("50-3 a"), "50-3");
+        assert_eq!(spanned("5-3 a"), SpannedParseError { error: ReversedRange, span: 0..3 });
+        assert_eq!(spanned("1-3"), SpannedParseError { error: MissingSeparator, span: 0..3 });
+
+        let options = PolicyParseOptions { max_position: Some(10) };
+        let err = PasswordPolicy::try_parse_spanned("1-20 a", &options).unwrap_err();
+        assert_eq!(err, SpannedParseError { error: RangeOutOfBounds { max: 10 }, span: 2..4 });
+        let err = PasswordPolicy::try_parse_spanned("0-2 a", &options).unwrap_err();
+        assert_eq!(err, SpannedParseError { error: RangeOutOfBounds { max: 10 }, span: 0..1 });
+
+        assert_eq!(PasswordPolicy::parse("1-x a"), None);
+        assert_eq!(PasswordPolicy::try_parse("1-x a"), Err(InvalidRange));
+    }
+
+    #[test]
+    fn test_password_policy_parse_many() {
+        let patterns = ['a', 'b', 'c', 'é'];
+        for i in 0..50_000u32 {
+            let first = i % 20 + 1;
+            let second = first + i % 7;
+            let pattern = patterns[(i % 4) as usize];
+            let line = format!("{}-{} {}", first, second, pattern);
+
+            assert_eq!(PasswordPolicy::parse(&line), Some(PasswordPolicy::new(pattern, first, second)));
+        }
+    }
+}
@@ -0,0 +1,853 @@
+use alloc::format;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{PasswordPolicyParseError, PositionError};
+
+/// A policy on passwords made of elements of type `T`, `char` by default. Only `char` policies
+/// can be parsed and validated against strings; other policies are validated with
+/// `validate_slice`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PasswordPolicy<T = char> {
+    pub(crate) pattern: T,
+    pub(crate) first: u32,
+    pub(crate) second: u32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PasswordPolicyMode {
+    /// The pattern must occur between `first` and `second` times, both inclusive.
+    SledRental,
+    /// Like `SledRental`, but the upper bound `second` is exclusive.
+    SledRentalExclusive,
+    /// Exactly one of the 1-indexed positions `first` and `second` must hold the pattern.
+    TobogganCorporate,
+    /// Like `TobogganCorporate`, but positions count from the end: position 1 is the last
+    /// character.
+    TobogganFromEnd,
+    /// The pattern must occur exactly `first` times; `second` is ignored.
+    ExactCount,
+    /// Both the `SledRental` and the `TobogganCorporate` rules must hold.
+    Both,
+}
+
+impl FromStr for PasswordPolicyMode {
+    type Err = PasswordPolicyModeParseError;
+
+    /// Accepts `sled`/`sledrental`, `sled-exclusive`/`sledrentalexclusive`,
+    /// `toboggan`/`toboggancorporate`, `toboggan-from-end`/`tobogganfromend` and
+    /// `exact`/`exactcount` and `both`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sled" | "sledrental" => Ok(PasswordPolicyMode::SledRental),
+            "sled-exclusive" | "sledrentalexclusive" => Ok(PasswordPolicyMode::SledRentalExclusive),
+            "toboggan" | "toboggancorporate" => Ok(PasswordPolicyMode::TobogganCorporate),
+            "toboggan-from-end" | "tobogganfromend" => Ok(PasswordPolicyMode::TobogganFromEnd),
+            "exact" | "exactcount" => Ok(PasswordPolicyMode::ExactCount),
+            "both" => Ok(PasswordPolicyMode::Both),
+            _ => Err(PasswordPolicyModeParseError(String::from(s))),
+        }
+    }
+}
+
+impl fmt::Display for PasswordPolicyMode {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PasswordPolicyMode::SledRental => write!(fmt, "sled"),
+            PasswordPolicyMode::SledRentalExclusive => write!(fmt, "sled-exclusive"),
+            PasswordPolicyMode::TobogganCorporate => write!(fmt, "toboggan"),
+            PasswordPolicyMode::TobogganFromEnd => write!(fmt, "toboggan-from-end"),
+            PasswordPolicyMode::ExactCount => write!(fmt, "exact"),
+            PasswordPolicyMode::Both => write!(fmt, "both"),
+        }
+    }
+}
+
+/// The string does not name a `PasswordPolicyMode`.
+#[derive(Debug, Eq, PartialEq)]
+pub struct PasswordPolicyModeParseError(pub String);
+
+impl fmt::Display for PasswordPolicyModeParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "unknown mode `{}` (expected `sled`, `sled-exclusive`, `toboggan`, `toboggan-from-end`, `exact` or `both`)", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PasswordPolicyModeParseError {}
+
+impl<T> PasswordPolicy<T> {
+    pub const fn new(pattern: T, first: u32, second: u32) -> PasswordPolicy<T> {
+        PasswordPolicy {
+            pattern,
+            first,
+            second,
+        }
+    }
+
+    /// The first bound: the minimum count in `SledRental` modes, or the first position in
+    /// `TobogganCorporate` mode. (Not named `min`, which would be shadowed by `Ord::min`.)
+    pub fn first(&self) -> u32 {
+        self.first
+    }
+
+    /// The second bound: the maximum count in `SledRental` modes, or the second position in
+    /// `TobogganCorporate` mode.
+    pub fn second(&self) -> u32 {
+        self.second
+    }
+
+    /// Returns the pattern and both bounds, in the order taken by `new`.
+    pub fn into_parts(self) -> (T, u32, u32) {
+        (self.pattern, self.first, self.second)
+    }
+
+    /// Swaps the bounds if `first` exceeds `second`, so that `5-2 a` becomes `2-5 a`. This only
+    /// makes sense for the count ranges of the `SledRental` modes: the toboggan modes name the
+    /// positions in order, and `ExactCount` only reads `first`.
+    pub fn normalized(self) -> PasswordPolicy<T> {
+        if self.first > self.second {
+            PasswordPolicy::new(self.pattern, self.second, self.first)
+        } else {
+            self
+        }
+    }
+
+    /// Tells whether no password can satisfy the policy under `mode`. This is the case when
+    /// `first == second` in the toboggan modes, as a position is compared with itself, and in
+    /// `SledRentalExclusive` mode, as the range is empty.
+    pub fn is_degenerate(&self, mode: PasswordPolicyMode) -> bool {
+        match mode {
+            PasswordPolicyMode::SledRental => self.first > self.second,
+            PasswordPolicyMode::SledRentalExclusive => self.first >= self.second,
+            PasswordPolicyMode::TobogganCorporate | PasswordPolicyMode::TobogganFromEnd => self.first == self.second,
+            PasswordPolicyMode::ExactCount => false,
+            PasswordPolicyMode::Both => {
+                self.is_degenerate(PasswordPolicyMode::SledRental) || self.is_degenerate(PasswordPolicyMode::TobogganCorporate)
+            }
+        }
+    }
+
+    /// Returns the items at the 1-indexed positions `first` and `second`, walking `items` only
+    /// once.
+    fn items_at<I>(&self, items: I) -> (Option<I::Item>, Option<I::Item>)
+        where I: Iterator, I::Item: Copy, {
+        // Positions are 1-indexed: a position of 0 never designates an item.
+        let first = self.first.checked_sub(1).map(|p| p as usize);
+        let second = self.second.checked_sub(1).map(|p| p as usize);
+        let end = first.max(second).map_or(0, |last| last + 1);
+
+        let mut first_item = None;
+        let mut second_item = None;
+        for (position, item) in items.enumerate().take(end) {
+            if Some(position) == first {
+                first_item = Some(item);
+            }
+            if Some(position) == second {
+                second_item = Some(item);
+            }
+        }
+
+        (first_item, second_item)
+    }
+}
+
+impl<T: Copy> PasswordPolicy<T> {
+    pub fn pattern(&self) -> T {
+        self.pattern
+    }
+}
+
+impl<T: PartialEq> PasswordPolicy<T> {
+    /// Validates a password given as a slice of elements, e.g. bytes. For `char` policies, this
+    /// gives the same result as `validate` on the corresponding string.
+    pub fn validate_slice(&self, mode: PasswordPolicyMode, password: &[T]) -> bool {
+        let matches = |item: Option<&T>| item.map(|item| *item == self.pattern);
+        match mode {
+            PasswordPolicyMode::SledRental => {
+                let count = password.iter().filter(|&item| *item == self.pattern).count() as u32;
+                self.first <= count && count <= self.second
+            }
+            PasswordPolicyMode::SledRentalExclusive => {
+                let count = password.iter().filter(|&item| *item == self.pattern).count() as u32;
+                self.first <= count && count < self.second
+            }
+            PasswordPolicyMode::TobogganCorporate => {
+                let (first_item, second_item) = self.items_at(password.iter());
+                matches(first_item) != matches(second_item)
+            }
+            PasswordPolicyMode::TobogganFromEnd => {
+                let (first_item, second_item) = self.items_at(password.iter().rev());
+                matches(first_item) != matches(second_item)
+            }
+            PasswordPolicyMode::ExactCount => {
+                password.iter().filter(|&item| *item == self.pattern).count() as u32 == self.first
+            }
+            PasswordPolicyMode::Both => {
+                self.validate_slice(PasswordPolicyMode::SledRental, password)
+                    && self.validate_slice(PasswordPolicyMode::TobogganCorporate, password)
+            }
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Creates a policy, checking that both bounds are at least 1 (positions are 1-indexed),
+    /// that `first` does not exceed `second`, and that the pattern is neither whitespace nor a
+    /// control character.
+    pub fn try_new(pattern: char, first: u32, second: u32) -> Result<PasswordPolicy, PasswordPolicyParseError> {
+        PasswordPolicy::check_pattern(pattern)?;
+        PasswordPolicy::check_range(first, second)?;
+        Ok(PasswordPolicy::new(pattern, first, second))
+    }
+
+    pub(crate) fn check_pattern(pattern: char) -> Result<(), PasswordPolicyParseError> {
+        if pattern.is_whitespace() || pattern.is_control() {
+            return Err(PasswordPolicyParseError::BadPattern);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_range(first: u32, second: u32) -> Result<(), PasswordPolicyParseError> {
+        if first == 0 || second == 0 {
+            return Err(PasswordPolicyParseError::RangeOutOfBounds { max: u32::MAX });
+        }
+        if first > second {
+            return Err(PasswordPolicyParseError::ReversedRange);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn parse_bound(s: &str) -> Result<u32, PasswordPolicyParseError> {
+        if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+            return Err(PasswordPolicyParseError::InvalidRange);
+        }
+
+        // The bound is made of digits only, so parsing can only fail if it overflows.
+        s.parse().map_err(|_| PasswordPolicyParseError::NumberOverflow)
+    }
+
+    pub(crate) fn parse_pattern(s: &str) -> Result<char, PasswordPolicyParseError> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => PasswordPolicy::check_pattern(c).map(|_| c),
+            _ => Err(PasswordPolicyParseError::BadPattern),
+        }
+    }
+
+    /// Counts how many times the pattern appears in `s`.
+    pub fn occurrences(&self, s: &str) -> u32 {
+        s.matches(self.pattern).count() as u32
+    }
+
+    pub fn validate(&self, mode: PasswordPolicyMode, s: &str) -> bool {
+        match mode {
+            PasswordPolicyMode::SledRental => {
+                let count = self.occurrences(s);
+                self.first <= count && count <= self.second
+            }
+            PasswordPolicyMode::SledRentalExclusive => {
+                let count = self.occurrences(s);
+                self.first <= count && count < self.second
+            }
+            PasswordPolicyMode::TobogganCorporate => {
+                let (first_char, second_char) = self.items_at(s.chars());
+                first_char.map(|c| c == self.pattern) != second_char.map(|c| c == self.pattern)
+            }
+            PasswordPolicyMode::TobogganFromEnd => {
+                let (first_char, second_char) = self.items_at(s.chars().rev());
+                first_char.map(|c| c == self.pattern) != second_char.map(|c| c == self.pattern)
+            }
+            PasswordPolicyMode::ExactCount => self.occurrences(s) == self.first,
+            PasswordPolicyMode::Both => {
+                self.validate(PasswordPolicyMode::SledRental, s) && self.validate(PasswordPolicyMode::TobogganCorporate, s)
+            }
+        }
+    }
+
+    /// Like `validate`, but the pattern matches characters of `s` regardless of their case.
+    pub fn validate_ignore_case(&self, mode: PasswordPolicyMode, s: &str) -> bool {
+        let matches = |c: char| c.to_lowercase().eq(self.pattern.to_lowercase());
+        match mode {
+            PasswordPolicyMode::SledRental => {
+                let count = s.chars().filter(|&c| matches(c)).count() as u32;
+                self.first <= count && count <= self.second
+            }
+            PasswordPolicyMode::SledRentalExclusive => {
+                let count = s.chars().filter(|&c| matches(c)).count() as u32;
+                self.first <= count && count < self.second
+            }
+            PasswordPolicyMode::TobogganCorporate => {
+                let (first_char, second_char) = self.items_at(s.chars());
+                first_char.map(matches) != second_char.map(matches)
+            }
+            PasswordPolicyMode::TobogganFromEnd => {
+                let (first_char, second_char) = self.items_at(s.chars().rev());
+                first_char.map(matches) != second_char.map(matches)
+            }
+            PasswordPolicyMode::ExactCount => s.chars().filter(|&c| matches(c)).count() as u32 == self.first,
+            PasswordPolicyMode::Both => {
+                self.validate_ignore_case(PasswordPolicyMode::SledRental, s)
+                    && self.validate_ignore_case(PasswordPolicyMode::TobogganCorporate, s)
+            }
+        }
+    }
+
+    /// Returns the characters of `s` at the 1-indexed positions `first` and `second`, i.e. what
+    /// `TobogganCorporate` mode compares with the pattern. A position outside of `s` gives `None`.
+    pub fn chars_at_positions(&self, s: &str) -> (Option<char>, Option<char>) {
+        self.items_at(s.chars())
+    }
+
+    /// Validates `s` under `TobogganCorporate` rules, but fails instead of treating a position
+    /// outside of `s` as a non-match.
+    pub fn validate_positions(&self, s: &str) -> Result<bool, PositionError> {
+        match self.items_at(s.chars()) {
+            (Some(first_char), Some(second_char)) => {
+                Ok((first_char == self.pattern) != (second_char == self.pattern))
+            }
+            (None, _) => Err(PositionError { position: self.first, length: s.chars().count() }),
+            (_, None) => Err(PositionError { position: self.second, length: s.chars().count() }),
+        }
+    }
+
+    /// Explains why `s` does not satisfy the policy under `mode`, or returns `None` if it does.
+    pub fn failure_reason(&self, mode: PasswordPolicyMode, s: &str) -> Option<String> {
+        if self.validate(mode, s) {
+            return None;
+        }
+
+        let reason = match mode {
+            PasswordPolicyMode::SledRental => {
+                format!("count {} not in {}..={}", self.occurrences(s), self.first, self.second)
+            }
+            PasswordPolicyMode::SledRentalExclusive => {
+                format!("count {} not in {}..{}", self.occurrences(s), self.first, self.second)
+            }
+            PasswordPolicyMode::TobogganCorporate => match self.items_at(s.chars()) {
+                (Some(c), Some(_)) if c == self.pattern => {
+                    format!("positions {} and {} both match", self.first, self.second)
+                }
+                _ => format!("neither position {} nor {} matches", self.first, self.second),
+            },
+            PasswordPolicyMode::TobogganFromEnd => match self.items_at(s.chars().rev()) {
+                (Some(c), Some(_)) if c == self.pattern => {
+                    format!("positions {} and {} from the end both match", self.first, self.second)
+                }
+                _ => format!("neither position {} nor {} from the end matches", self.first, self.second),
+            },
+            PasswordPolicyMode::ExactCount => format!("count {} is not {}", self.occurrences(s), self.first),
+            PasswordPolicyMode::Both => {
+                return self.failure_reason(PasswordPolicyMode::SledRental, s)
+                    .or_else(|| self.failure_reason(PasswordPolicyMode::TobogganCorporate, s));
+            }
+        };
+
+        Some(reason)
+    }
+
+    /// Describes what validating `s` under `mode` looks at: the count of the pattern, or the
+    /// characters at both positions.
+    pub(crate) fn explain(&self, mode: PasswordPolicyMode, s: &str) -> String {
+        // `validate` treats a position past the end as holding neither pattern, which makes the
+        // match "exactly one" in name only, so those positions are spelled out instead.
+        let positions = |(first_char, second_char): (Option<char>, Option<char>)| match (first_char, second_char) {
+            (Some(first_char), Some(second_char)) => {
+                format!("'{}','{}' → exactly-one={}", first_char, second_char, self.validate(mode, s))
+            }
+            _ => {
+                let describe = |c: Option<char>, position| c.map_or(format!("pos {} out of range", position), |c| format!("'{}'", c));
+                format!("{}, {}", describe(first_char, self.first), describe(second_char, self.second))
+            }
+        };
+        match mode {
+            PasswordPolicyMode::SledRental => {
+                format!("'{}' appears {} times, allowed {}..={}", self.pattern, self.occurrences(s), self.first, self.second)
+            }
+            PasswordPolicyMode::SledRentalExclusive => {
+                format!("'{}' appears {} times, allowed {}..{}", self.pattern, self.occurrences(s), self.first, self.second)
+            }
+            PasswordPolicyMode::TobogganCorporate => {
+                format!("positions {},{} → {}", self.first, self.second, positions(self.items_at(s.chars())))
+            }
+            PasswordPolicyMode::TobogganFromEnd => {
+                format!("positions {},{} from the end → {}", self.first, self.second, positions(self.items_at(s.chars().rev())))
+            }
+            PasswordPolicyMode::ExactCount => {
+                format!("'{}' appears {} times, required {}", self.pattern, self.occurrences(s), self.first)
+            }
+            PasswordPolicyMode::Both => {
+                format!("{}; {}", self.explain(PasswordPolicyMode::SledRental, s), self.explain(PasswordPolicyMode::TobogganCorporate, s))
+            }
+        }
+    }
+}
+
+/// The policy `1-1 a`.
+impl Default for PasswordPolicy {
+    fn default() -> PasswordPolicy {
+        PasswordPolicy::new('a', 1, 1)
+    }
+}
+
+/// Policies are ordered by `first`, then `second`, then `pattern`.
+impl<T: Ord> Ord for PasswordPolicy<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.first, self.second, &self.pattern).cmp(&(other.first, other.second, &other.pattern))
+    }
+}
+
+impl<T: Ord> PartialOrd for PasswordPolicy<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for PasswordPolicy<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}-{} {}", self.first, self.second, self.pattern)
+    }
+}
+
+/// A policy whose pattern is a non-empty substring rather than a single character, e.g. `1-3 ab`.
+///
+/// Occurrences are counted without overlap, scanning from the left: `aa` appears twice in `aaaa`
+/// and once in `aaa`. In `TobogganCorporate` mode, a position matches when an occurrence of the
+/// pattern starts there.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SubstringPolicy {
+    pattern: String,
+    first: u32,
+    second: u32,
+}
+
+impl SubstringPolicy {
+    pub fn new(pattern: &str, first: u32, second: u32) -> SubstringPolicy {
+        SubstringPolicy {
+            pattern: String::from(pattern),
+            first,
+            second,
+        }
+    }
+
+    pub fn try_new(pattern: &str, first: u32, second: u32) -> Result<SubstringPolicy, PasswordPolicyParseError> {
+        if pattern.is_empty() || pattern.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(PasswordPolicyParseError::BadPattern);
+        }
+        PasswordPolicy::check_range(first, second)?;
+
+        Ok(SubstringPolicy::new(pattern, first, second))
+    }
+
+    /// Counts the non-overlapping occurrences of the pattern in `s`.
+    pub fn occurrences(&self, s: &str) -> u32 {
+        s.matches(self.pattern.as_str()).count() as u32
+    }
+
+    /// Counts the occurrences of the pattern in `s`, including overlapping ones: `aa` appears
+    /// three times in `aaaa`.
+    pub fn occurrences_overlapping(&self, s: &str) -> u32 {
+        s.char_indices()
+            .filter(|&(offset, _)| s[offset..].starts_with(self.pattern.as_str()))
+            .count() as u32
+    }
+
+    pub fn validate(&self, mode: PasswordPolicyMode, s: &str) -> bool {
+        match mode {
+            PasswordPolicyMode::SledRental => {
+                let count = self.occurrences(s);
+                self.first <= count && count <= self.second
+            }
+            PasswordPolicyMode::SledRentalExclusive => {
+                let count = self.occurrences(s);
+                self.first <= count && count < self.second
+            }
+            PasswordPolicyMode::TobogganCorporate => {
+                self.starts_at(s, self.first) != self.starts_at(s, self.second)
+            }
+            PasswordPolicyMode::TobogganFromEnd => {
+                // Position `p` from the end is position `length - p + 1` from the start.
+                let length = s.chars().count() as u32;
+                let starts_at_from_end = |position: u32| {
+                    let forward = length.checked_sub(position.checked_sub(1)?)?;
+                    self.starts_at(s, forward)
+                };
+                starts_at_from_end(self.first) != starts_at_from_end(self.second)
+            }
+            PasswordPolicyMode::ExactCount => self.occurrences(s) == self.first,
+            PasswordPolicyMode::Both => {
+                self.validate(PasswordPolicyMode::SledRental, s) && self.validate(PasswordPolicyMode::TobogganCorporate, s)
+            }
+        }
+    }
+
+    /// Tells whether the pattern starts at the 1-indexed character `position` of `s`, or `None`
+    /// if `s` has no such position.
+    fn starts_at(&self, s: &str, position: u32) -> Option<bool> {
+        let index = position.checked_sub(1)? as usize;
+        let (offset, _) = s.char_indices().nth(index)?;
+
+        Some(s[offset..].starts_with(self.pattern.as_str()))
+    }
+}
+
+impl fmt::Display for SubstringPolicy {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}-{} {}", self.first, self.second, self.pattern)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::PasswordPolicyMode::{Both, ExactCount, SledRental, SledRentalExclusive, TobogganCorporate, TobogganFromEnd};
+
+    use crate::*;
+
+    #[test]
+    fn test_password_policy_ord() {
+        assert!(PasswordPolicy::new('z', 1, 3) < PasswordPolicy::new('a', 2, 3));
+        assert!(PasswordPolicy::new('z', 1, 3) < PasswordPolicy::new('a', 1, 4));
+        assert!(PasswordPolicy::new('a', 1, 3) < PasswordPolicy::new('b', 1, 3));
+        assert_eq!(PasswordPolicy::new('a', 1, 3).cmp(&PasswordPolicy::new('a', 1, 3)), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_password_policy_to_string() {
+        assert_eq!(PasswordPolicy::new('h', 9, 15).to_string(), "9-15 h");
+    }
+
+    #[test]
+    fn test_password_policy_try_new() {
+        use PasswordPolicyParseError::*;
+
+        assert_eq!(PasswordPolicy::try_new('a', 1, 3), Ok(PasswordPolicy::new('a', 1, 3)));
+        assert_eq!(PasswordPolicy::try_new('a', 2, 2), Ok(PasswordPolicy::new('a', 2, 2)));
+        assert_eq!(PasswordPolicy::try_new('a', 5, 2), Err(ReversedRange));
+        assert_eq!(PasswordPolicy::try_new('a', 0, 2), Err(RangeOutOfBounds { max: u32::MAX }));
+        assert_eq!(PasswordPolicy::try_parse("5-2 a"), Err(ReversedRange));
+    }
+
+    #[test]
+    fn test_password_policy_getters() {
+        let policy = PasswordPolicy::parse("2-9 c").unwrap();
+
+        assert_eq!(policy.pattern(), 'c');
+        assert_eq!(policy.first(), 2);
+        assert_eq!(policy.second(), 9);
+        assert_eq!(policy.into_parts(), ('c', 2, 9));
+    }
+
+    #[test]
+    fn test_password_policy_whitespace_pattern() {
+        use PasswordPolicyParseError::*;
+
+        assert_eq!(PasswordPolicy::try_new(' ', 1, 3), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_new('\t', 1, 3), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_new('\u{7}', 1, 3), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_parse("1-3 \t"), Err(BadPattern));
+        assert_eq!(PasswordPolicy::try_parse("1-3 \u{7}"), Err(BadPattern));
+        assert_eq!(PasswordEntry::parse_csv("1,3,\"\t\",abc"), None);
+        assert_eq!(SubstringPolicy::try_new("a\u{0}", 1, 3), Err(BadPattern));
+    }
+
+    #[test]
+    fn test_substring_policy_occurrences() {
+        let policy = SubstringPolicy::new("aa", 1, 3);
+
+        assert_eq!(policy.occurrences("aaaa"), 2);
+        assert_eq!(policy.occurrences("aaa"), 1);
+        assert_eq!(policy.occurrences("abab"), 0);
+        assert_eq!(SubstringPolicy::new("ab", 1, 3).occurrences("abab"), 2);
+    }
+
+    #[test]
+    fn test_substring_policy_occurrences_overlapping() {
+        let policy = SubstringPolicy::new("aa", 1, 3);
+
+        assert_eq!(policy.occurrences("aaaa"), 2);
+        assert_eq!(policy.occurrences_overlapping("aaaa"), 3);
+        assert_eq!(policy.occurrences_overlapping("aaa"), 2);
+        assert_eq!(policy.occurrences_overlapping("abab"), 0);
+        assert_eq!(SubstringPolicy::new("ab", 1, 3).occurrences_overlapping("abab"), 2);
+        assert_eq!(SubstringPolicy::new("αα", 1, 3).occurrences_overlapping("ααα"), 2);
+    }
+
+    #[test]
+    fn test_substring_policy_validate() {
+        assert!(SubstringPolicy::new("aa", 2, 2).validate(SledRental, "aaaa"));
+        assert!(!SubstringPolicy::new("aa", 3, 4).validate(SledRental, "aaaa"));
+        assert!(!SubstringPolicy::new("aa", 1, 2).validate(SledRentalExclusive, "aaaa"));
+
+        assert!(SubstringPolicy::new("ab", 1, 2).validate(TobogganCorporate, "abab"));
+        assert!(!SubstringPolicy::new("ab", 1, 3).validate(TobogganCorporate, "abab"));
+        assert!(!SubstringPolicy::new("ab", 2, 4).validate(TobogganCorporate, "abab"));
+        assert!(SubstringPolicy::new("βγ", 2, 3).validate(TobogganCorporate, "αβγ"));
+    }
+
+    #[test]
+    fn test_password_policy_validate_positions() {
+        assert_eq!(PasswordPolicy::new('a', 1, 3).validate_positions("abcde"), Ok(true));
+        assert_eq!(PasswordPolicy::new('b', 1, 3).validate_positions("cdefg"), Ok(false));
+        assert_eq!(PasswordPolicy::new('c', 2, 9).validate_positions("ccccccccc"), Ok(false));
+        assert_eq!(PasswordPolicy::new('é', 1, 2).validate_positions("aé"), Ok(true));
+
+        assert_eq!(
+            PasswordPolicy::new('a', 1, 6).validate_positions("abcde"),
+            Err(PositionError { position: 6, length: 5 })
+        );
+        assert_eq!(
+            PasswordPolicy::new('a', 7, 8).validate_positions("abcde"),
+            Err(PositionError { position: 7, length: 5 })
+        );
+        assert_eq!(
+            PasswordPolicy::new('a', 0, 1).validate_positions("abcde"),
+            Err(PositionError { position: 0, length: 5 })
+        );
+        assert_eq!(
+            PasswordPolicy::new('a', 1, 6).validate_positions("abcde").unwrap_err().to_string(),
+            "position 6 is out of range for a password of 5 characters"
+        );
+        assert!(PasswordPolicy::new('a', 1, 6).validate(TobogganCorporate, "abcde"));
+    }
+
+    #[test]
+    fn test_password_policy_validate_exclusive() {
+        let policy = PasswordPolicy::new('a', 1, 3);
+
+        assert!(policy.validate(SledRental, "aaa"));
+        assert!(!policy.validate(SledRentalExclusive, "aaa"));
+        assert!(policy.validate(SledRental, "aab"));
+        assert!(policy.validate(SledRentalExclusive, "aab"));
+        assert!(!policy.validate(SledRental, "bbb"));
+        assert!(!policy.validate(SledRentalExclusive, "bbb"));
+    }
+
+    #[test]
+    fn test_password_policy_normalized() {
+        assert_eq!(PasswordPolicy::new('a', 5, 2).normalized(), PasswordPolicy::new('a', 2, 5));
+        assert_eq!(PasswordPolicy::new('a', 2, 5).normalized(), PasswordPolicy::new('a', 2, 5));
+        assert_eq!(PasswordPolicy::new('a', 3, 3).normalized(), PasswordPolicy::new('a', 3, 3));
+        assert!(PasswordPolicy::new('a', 5, 2).normalized().validate(SledRental, "aaa"));
+    }
+
+    #[test]
+    fn test_password_policy_default() {
+        const POLICY: PasswordPolicy = PasswordPolicy::new('b', 2, 4);
+
+        assert_eq!(PasswordPolicy::default(), PasswordPolicy::new('a', 1, 1));
+        assert_eq!(PasswordPolicy::default().to_string(), "1-1 a");
+        assert_eq!(POLICY.to_string(), "2-4 b");
+    }
+
+    #[test]
+    fn test_password_policy_validate_both() {
+        let policy = PasswordPolicy::new('a', 1, 3);
+
+        assert!(policy.validate(Both, "abcde"));
+        assert!(policy.validate(SledRental, "aaa") && !policy.validate(Both, "aaa"));
+        assert!(policy.validate(TobogganCorporate, "abbaaa") && !policy.validate(Both, "abbaaa"));
+        assert!(!policy.validate(Both, "bbb"));
+        assert!(PasswordPolicy::new('a', 2, 2).is_degenerate(Both));
+        assert_eq!(policy.failure_reason(Both, "abbaaa"), Some(String::from("count 4 not in 1..=3")));
+        assert_eq!(policy.failure_reason(Both, "aaa"), Some(String::from("positions 1 and 3 both match")));
+        assert_eq!("both".parse::<PasswordPolicyMode>(), Ok(Both));
+    }
+
+    #[test]
+    fn test_password_policy_validate_exact_count() {
+        let policy = PasswordPolicy::new('a', 2, 3);
+
+        assert!(policy.validate(ExactCount, "aab"));
+        assert!(!policy.validate(ExactCount, "aaab"));
+        assert!(policy.validate(SledRental, "aaab"));
+        assert!(!policy.validate(ExactCount, "abc"));
+        assert!(policy.validate_ignore_case(ExactCount, "aAb"));
+        assert!(policy.validate_slice(ExactCount, &['a', 'b', 'a']));
+        assert!(!policy.is_degenerate(ExactCount));
+        assert_eq!(policy.failure_reason(ExactCount, "aaab"), Some(String::from("count 3 is not 2")));
+        assert_eq!("exact".parse::<PasswordPolicyMode>(), Ok(ExactCount));
+        assert_eq!(ExactCount.to_string(), "exact");
+    }
+
+    #[test]
+    fn test_password_policy_is_degenerate() {
+        let policy = PasswordPolicy::parse("2-2 a").unwrap();
+
+        assert!(policy.is_degenerate(TobogganCorporate));
+        assert!(policy.is_degenerate(SledRentalExclusive));
+        assert!(!policy.is_degenerate(SledRental));
+        assert!(!PasswordPolicy::parse("1-3 a").unwrap().is_degenerate(TobogganCorporate));
+
+        for password in &["", "a", "aa", "ab", "ba", "bb", "aaa", "bab"] {
+            assert!(!policy.validate(TobogganCorporate, password));
+        }
+        assert!(policy.validate(SledRental, "aba"));
+    }
+
+    #[test]
+    fn test_password_policy_validate_zero_position() {
+        assert!(PasswordPolicy::new('a', 0, 1).validate(TobogganCorporate, "abc"));
+        assert!(PasswordPolicy::new('b', 2, 0).validate(TobogganCorporate, "abc"));
+        assert!(!PasswordPolicy::new('a', 0, 0).validate(TobogganCorporate, "abc"));
+    }
+
+    #[test]
+    fn test_password_policy_validate_long_password() {
+        let mut password = "b".repeat(10_000);
+        password.push('a');
+        password.push('b');
+
+        assert!(PasswordPolicy::new('a', 10_001, 10_002).validate(TobogganCorporate, &password));
+        assert!(PasswordPolicy::new('a', 10_002, 10_001).validate(TobogganCorporate, &password));
+        assert!(!PasswordPolicy::new('a', 10_000, 10_002).validate(TobogganCorporate, &password));
+        assert!(!PasswordPolicy::new('b', 10_000, 10_002).validate(TobogganCorporate, &password));
+        assert!(PasswordPolicy::new('a', 10_001, 10_005).validate(TobogganCorporate, &password));
+    }
+
+    #[test]
+    fn test_password_policy_validate_unicode() {
+        assert!(PasswordPolicy::parse("1-3 é").unwrap().validate(SledRental, "café"));
+        assert!(!PasswordPolicy::parse("2-3 é").unwrap().validate(SledRental, "café"));
+        assert!(PasswordPolicy::parse("2-3 α").unwrap().validate(SledRental, "αβγαδ"));
+
+        assert!(PasswordPolicy::parse("1-4 é").unwrap().validate(TobogganCorporate, "éééa"));
+        assert!(!PasswordPolicy::parse("1-4 é").unwrap().validate(TobogganCorporate, "éaaé"));
+        assert!(PasswordPolicy::parse("2-5 α").unwrap().validate(TobogganCorporate, "βαγδε"));
+        assert!(PasswordPolicy::parse("2-3 字").unwrap().validate(TobogganCorporate, "漢字a"));
+    }
+
+    #[test]
+    fn test_password_policy_occurrences() {
+        let policy = PasswordPolicy::new('a', 1, 3);
+        assert_eq!(policy.occurrences(""), 0);
+        assert_eq!(policy.occurrences("bcd"), 0);
+        assert_eq!(policy.occurrences("abcde"), 1);
+        assert_eq!(policy.occurrences("aaaa"), 4);
+        assert_eq!(policy.occurrences("abababa"), 4);
+        assert_eq!(PasswordPolicy::new('é', 1, 3).occurrences("éeéeé"), 3);
+    }
+
+    #[test]
+    fn test_password_policy_mode_copy() {
+        let mode = SledRental;
+        let copy = mode;
+
+        assert_eq!(mode, copy);
+        assert_ne!(mode, TobogganCorporate);
+        assert_eq!(format!("{:?}", mode), "SledRental");
+    }
+
+    #[test]
+    fn test_password_policy_mode_from_str() {
+        assert_eq!("sled".parse(), Ok(SledRental));
+        assert_eq!("SledRental".parse(), Ok(SledRental));
+        assert_eq!("SLED".parse(), Ok(SledRental));
+        assert_eq!("sled-exclusive".parse(), Ok(SledRentalExclusive));
+        assert_eq!("SledRentalExclusive".parse(), Ok(SledRentalExclusive));
+        assert_eq!("toboggan".parse(), Ok(TobogganCorporate));
+        assert_eq!("TobogganCorporate".parse(), Ok(TobogganCorporate));
+        assert_eq!("toBOGgan".parse(), Ok(TobogganCorporate));
+        assert_eq!("toboggan-from-end".parse(), Ok(TobogganFromEnd));
+        assert_eq!("TobogganFromEnd".parse(), Ok(TobogganFromEnd));
+
+        assert_eq!("".parse::<PasswordPolicyMode>(), Err(PasswordPolicyModeParseError(String::from(""))));
+        assert_eq!("sled rental".parse::<PasswordPolicyMode>(), Err(PasswordPolicyModeParseError(String::from("sled rental"))));
+        assert_eq!(
+            "Bob".parse::<PasswordPolicyMode>().unwrap_err().to_string(),
+            "unknown mode `Bob` (expected `sled`, `sled-exclusive`, `toboggan`, `toboggan-from-end`, `exact` or `both`)"
+        );
+    }
+
+    #[test]
+    fn test_password_policy_mode_to_string() {
+        assert_eq!(SledRental.to_string(), "sled");
+        assert_eq!(SledRentalExclusive.to_string(), "sled-exclusive");
+        assert_eq!(TobogganCorporate.to_string(), "toboggan");
+        assert_eq!(TobogganFromEnd.to_string(), "toboggan-from-end");
+        assert_eq!(SledRental.to_string().parse(), Ok(SledRental));
+    }
+
+    #[test]
+    fn test_password_policy_validate_from_end() {
+        let policy = PasswordPolicy::new('a', 1, 3);
+
+        assert!(policy.validate(TobogganCorporate, "abcde"));
+        assert!(!policy.validate(TobogganFromEnd, "abcde"));
+        assert!(!policy.validate(TobogganCorporate, "edcba"));
+        assert!(policy.validate(TobogganFromEnd, "edcba"));
+        assert!(policy.validate(TobogganFromEnd, "bba"));
+        assert!(!policy.validate(TobogganFromEnd, "aba"));
+        assert!(policy.validate(TobogganFromEnd, "a"));
+        assert!(!PasswordPolicy::new('a', 0, 6).validate(TobogganFromEnd, "aaaaa"));
+        assert!(PasswordPolicy::new('é', 1, 2).validate(TobogganFromEnd, "xéy"));
+
+        assert_eq!(policy.failure_reason(TobogganFromEnd, "abcde"), Some(String::from("neither position 1 nor 3 from the end matches")));
+        assert_eq!(policy.failure_reason(TobogganFromEnd, "aba"), Some(String::from("positions 1 and 3 from the end both match")));
+        assert!(PasswordPolicy::new('a', 2, 2).is_degenerate(TobogganFromEnd));
+
+        let substring = SubstringPolicy::new("ab", 1, 3);
+        assert!(substring.validate(TobogganFromEnd, "xabx"));
+        assert!(!substring.validate(TobogganFromEnd, "abxx"));
+    }
+
+    #[test]
+    fn test_password_policy_validate_slice() {
+        let policy = PasswordPolicy::new(b'a', 1, 3);
+
+        assert!(policy.validate_slice(SledRental, b"abcde"));
+        assert!(!policy.validate_slice(SledRental, b"aaaa"));
+        assert!(!policy.validate_slice(SledRentalExclusive, b"aaa"));
+        assert!(policy.validate_slice(TobogganCorporate, b"abcde"));
+        assert!(!policy.validate_slice(TobogganCorporate, b"aba"));
+        assert!(policy.validate_slice(TobogganFromEnd, b"edcba"));
+        assert_eq!(policy.to_string(), "1-3 97");
+
+        let policy = PasswordPolicy::new('é', 1, 2);
+        let password: Vec<char> = "éa".chars().collect();
+        for &mode in &[SledRental, SledRentalExclusive, TobogganCorporate, TobogganFromEnd, ExactCount, Both] {
+            assert_eq!(policy.validate_slice(mode, &password), policy.validate(mode, "éa"));
+        }
+    }
+
+    #[test]
+    fn test_password_policy_validate_ignore_case() {
+        let policy = PasswordPolicy::new('a', 1, 3);
+        assert!(!policy.validate(SledRental, "AbAbA"));
+        assert!(policy.validate_ignore_case(SledRental, "AbAba"));
+        assert!(!PasswordPolicy::new('a', 1, 2).validate_ignore_case(SledRental, "AbAba"));
+        assert!(PasswordPolicy::new('A', 3, 3).validate_ignore_case(SledRental, "AbAba"));
+
+        assert!(!policy.validate_ignore_case(TobogganCorporate, "AbAba"));
+        assert!(!PasswordPolicy::new('a', 1, 2).validate(TobogganCorporate, "AbAba"));
+        assert!(PasswordPolicy::new('a', 1, 2).validate_ignore_case(TobogganCorporate, "AbAba"));
+        assert!(PasswordPolicy::new('a', 1, 2).validate_ignore_case(TobogganFromEnd, "AbAba"));
+
+        let entry = PasswordEntry::parse("1-2 é: ÉÉé").unwrap();
+        assert!(!entry.is_valid_ignore_case(SledRental));
+        assert!(entry.is_valid(SledRental));
+    }
+
+    #[test]
+    fn test_password_policy_chars_at_positions() {
+        assert_eq!(PasswordPolicy::new('a', 1, 3).chars_at_positions("abcde"), (Some('a'), Some('c')));
+        assert_eq!(PasswordPolicy::new('c', 2, 9).chars_at_positions("ccccccccc"), (Some('c'), Some('c')));
+        assert_eq!(PasswordPolicy::new('é', 2, 3).chars_at_positions("aéb"), (Some('é'), Some('b')));
+        assert_eq!(PasswordPolicy::new('a', 2, 6).chars_at_positions("abcde"), (Some('b'), None));
+        assert_eq!(PasswordPolicy::new('a', 0, 1).chars_at_positions("abcde"), (None, Some('a')));
+    }
+
+    #[test]
+    fn test_password_policy_validate() {
+        assert!(PasswordPolicy::parse("1-3 a").unwrap().validate(SledRental, "abcde"));
+        assert!(!PasswordPolicy::parse("1-3 b").unwrap().validate(SledRental, "cdefg"));
+        assert!(PasswordPolicy::parse("2-9 c").unwrap().validate(SledRental, "ccccccccc"));
+
+        assert!(PasswordPolicy::parse("1-3 a").unwrap().validate(TobogganCorporate, "abcde"));
+        assert!(!PasswordPolicy::parse("1-3 b").unwrap().validate(TobogganCorporate, "cdefg"));
+        assert!(!PasswordPolicy::parse("2-9 c").unwrap().validate(TobogganCorporate, "ccccccccc"));
+    }
+}
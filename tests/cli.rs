@@ -0,0 +1,280 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_advent-of-code-2020-day-2"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn run_with_input(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_advent-of-code-2020-day-2"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_verbose() {
+    let output = run(&["tests/fixtures/sample.txt", "--mode", "sled", "--verbose"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), vec![
+        "1-3 a: abcde -> VALID",
+        "1-3 b: cdefg -> INVALID",
+        "2-9 c: ccccccccc -> VALID",
+        "There are 2 / 3 valid passwords (1 invalid passwords)",
+    ]);
+
+    let output = run(&["tests/fixtures/sample.txt", "--verbose"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("1-3 a: abcde -> VALID\n"));
+    assert!(stdout.contains("2-9 c: ccccccccc -> INVALID\n"));
+}
+
+#[test]
+fn test_not_verbose_by_default() {
+    let output = run(&["tests/fixtures/sample.txt"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout, "There are 1 / 3 valid passwords (2 invalid passwords)\n");
+}
+
+#[test]
+fn test_strict_exit_code() {
+    assert_eq!(run(&["tests/fixtures/sample.txt"]).status.code(), Some(0));
+    assert_eq!(run(&["tests/fixtures/sample.txt", "--strict"]).status.code(), Some(1));
+    assert_eq!(run(&["tests/fixtures/valid.txt", "--mode", "sled", "--strict"]).status.code(), Some(0));
+    assert_eq!(run(&["tests/fixtures/valid.txt", "--mode", "toboggan", "--strict"]).status.code(), Some(1));
+}
+
+#[test]
+fn test_count_only() {
+    let output = run(&["tests/fixtures/sample.txt", "--count-only"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+
+    let output = run(&["tests/fixtures/sample.txt", "--count-only", "--mode", "sled", "--verbose"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n");
+}
+
+#[test]
+fn test_multiple_files() {
+    let output = run(&["tests/fixtures/sample.txt", "tests/fixtures/valid.txt", "--mode", "sled"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), vec![
+        "tests/fixtures/sample.txt: There are 2 / 3 valid passwords (1 invalid passwords)",
+        "tests/fixtures/valid.txt: There are 2 / 2 valid passwords (0 invalid passwords)",
+        "Total: There are 4 / 5 valid passwords (1 invalid passwords)",
+    ]);
+
+    let output = run(&["tests/fixtures/sample.txt", "tests/fixtures/valid.txt", "--count-only"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n");
+}
+
+#[test]
+fn test_stats() {
+    let output = run(&["tests/fixtures/repeated.txt", "--stats"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), vec![
+        "Pattern frequencies:",
+        "  a: 3",
+        "  b: 2",
+        "  c: 1",
+        "Average distinct characters per password: 2.33",
+        "There are 2 / 6 valid passwords (4 invalid passwords)",
+    ]);
+}
+
+#[test]
+fn test_progress() {
+    let output = run(&["tests/fixtures/sample.txt", "--progress", "--mode", "sled"]);
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 2 / 3 valid passwords (1 invalid passwords)\n");
+    assert!(String::from_utf8(output.stderr).unwrap().ends_with("Progress: 100%\n"));
+}
+
+#[test]
+fn test_verbose_without_colors() {
+    let output = Command::new(env!("CARGO_BIN_EXE_advent-of-code-2020-day-2"))
+        .args(["tests/fixtures/sample.txt", "--verbose"])
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("INVALID"));
+    assert!(!stdout.contains('\x1b'));
+
+    // Output is not a terminal here, so colors stay off even without `NO_COLOR`.
+    let stdout = String::from_utf8(run(&["tests/fixtures/sample.txt", "--verbose"]).stdout).unwrap();
+    assert!(!stdout.contains('\x1b'));
+}
+
+#[test]
+fn test_ignore_case() {
+    let output = run(&["tests/fixtures/mixed_case.txt", "--mode", "sled", "--count-only"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n");
+
+    let output = run(&["tests/fixtures/mixed_case.txt", "--mode", "sled", "--count-only", "--ignore-case"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+
+    let output = run(&["tests/fixtures/mixed_case.txt", "--count-only", "--ignore-case"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+}
+
+#[test]
+fn test_dry_run() {
+    let output = run(&["tests/fixtures/partial.txt", "--dry-run", "--mode", "sled"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout, "tests/fixtures/partial.txt: 2 entries parsed, 1 lines skipped; would validate in sled mode\n");
+    assert!(!stdout.contains("valid passwords"));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("skipped 1 unparseable lines: 2"));
+}
+
+#[test]
+fn test_delimiter() {
+    let output = run(&["tests/fixtures/pipe_delimited.txt", "--delimiter", "|", "--mode", "sled"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 2 / 3 valid passwords (1 invalid passwords)\n");
+
+    let output = run(&["tests/fixtures/pipe_delimited.txt", "--delimiter", "||"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run(&["tests/fixtures/pipe_delimited.txt", "--delimiter", "#"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_csv_quoted_hash() {
+    let output = run(&["tests/fixtures/quoted_hash.txt", "--input-format", "csv", "--mode", "sled"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 2 / 3 valid passwords (1 invalid passwords)\n");
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
+
+#[test]
+fn test_sample() {
+    let output = run(&["tests/fixtures/partial.txt", "--mode", "sled", "--sample", "1"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 1 / 1 valid passwords (0 invalid passwords)\n");
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+
+    let output = run(&["tests/fixtures/partial.txt", "--mode", "sled", "--sample", "2"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 2 / 2 valid passwords (0 invalid passwords)\n");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("skipped 1 unparseable lines: 2"));
+
+    assert_eq!(run(&["tests/fixtures/sample.txt", "--sample", "many"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_fingerprint() {
+    let first = String::from_utf8(run(&["tests/fixtures/sample.txt", "--fingerprint"]).stdout).unwrap();
+    let second = String::from_utf8(run(&["tests/fixtures/sample.txt", "--fingerprint"]).stdout).unwrap();
+
+    assert!(first.starts_with("Fingerprint of tests/fixtures/sample.txt: "));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_only_patterns() {
+    let output = run(&["tests/fixtures/sample.txt", "--mode", "sled", "--only-patterns", "ab"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 1 / 2 valid passwords (1 invalid passwords)\n");
+}
+
+#[test]
+fn test_except_patterns() {
+    let output = run(&["tests/fixtures/sample.txt", "--mode", "sled", "--except-patterns", "ab"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 1 / 1 valid passwords (0 invalid passwords)\n");
+
+    let output = run(&["tests/fixtures/sample.txt", "--mode", "sled", "--only-patterns", "ab", "--except-patterns", "a"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 0 / 1 valid passwords (1 invalid passwords)\n");
+}
+
+#[test]
+fn test_missing_input() {
+    let output = run(&["tests/fixtures/missing.txt"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.starts_with("Error: cannot read tests/fixtures/missing.txt: "), "{}", stderr);
+}
+
+#[test]
+fn test_comments() {
+    let output = run(&["tests/fixtures/commented.txt", "--mode", "sled"]);
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 2 / 3 valid passwords (1 invalid passwords)\n");
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
+
+#[test]
+fn test_time() {
+    let output = run(&["tests/fixtures/sample.txt", "--time"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 1 / 3 valid passwords (2 invalid passwords)\n");
+    let lines: Vec<_> = stderr.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("Time reading and parsing tests/fixtures/sample.txt: "));
+    assert!(lines[1].starts_with("Time validating tests/fixtures/sample.txt: "));
+}
+
+#[test]
+fn test_max_errors() {
+    let output = run(&["tests/fixtures/malformed.txt", "--max-errors", "2"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "");
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "Error: tests/fixtures/malformed.txt: stopped at line 3 after 2 unparseable lines\n");
+
+    let output = run(&["tests/fixtures/malformed.txt", "--max-errors", "4", "--mode", "sled"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 2 / 2 valid passwords (0 invalid passwords)\n");
+
+    let output = run(&["tests/fixtures/valid.txt", "--max-errors", "0"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
+
+#[test]
+fn test_invalid_utf8_line() {
+    let output = run(&["tests/fixtures/invalid_utf8.txt", "--mode", "sled"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 2 / 3 valid passwords (1 invalid passwords)\n");
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "Warning: tests/fixtures/invalid_utf8.txt: skipped 1 unparseable lines: 2\n");
+
+    let output = run(&["tests/fixtures/invalid_utf8.txt", "--dry-run"]);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("3 entries parsed, 1 lines skipped"));
+}
+
+#[test]
+fn test_repl_ignore_case() {
+    let output = run_with_input(&["repl", "--mode", "sled", "--ignore-case"], "3-3 a: AbAba\n");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "VALID\n");
+
+    let output = run_with_input(&["repl", "--mode", "sled"], "3-3 a: AbAba\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "INVALID\n");
+}
+
+#[test]
+fn test_repl_delimiter() {
+    let output = run_with_input(&["repl", "--mode", "sled", "--delimiter", "|"], "1-3 a | abcde\n1-3 a: abcde\n");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "VALID\nerror: cannot parse `1-3 a: abcde`: missing separator between the policy and the password\n"
+    );
+}
@@ -0,0 +1,27 @@
+use std::process::{Command, Output};
+
+/// Runs the binary from the directory holding the config fixture.
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_advent-of-code-2020-day-2"))
+        .current_dir("tests/fixtures/config")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_config_defaults() {
+    let output = run(&[]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "{\"total\":3,\"valid\":2,\"invalid\":1}\n");
+}
+
+#[test]
+fn test_config_overridden_by_arguments() {
+    let output = run(&["--mode", "toboggan", "--format", "text"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "There are 1 / 3 valid passwords (2 invalid passwords)\n");
+
+    let output = run(&["../valid.txt"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "{\"total\":2,\"valid\":2,\"invalid\":0}\n");
+}
@@ -0,0 +1,107 @@
+//! Property tests over pseudo-random policies and passwords. Each property is checked on a fixed
+//! number of cases drawn from a seeded generator, so failures are reproducible.
+
+use advent_of_code_2020_day_2::{PasswordEntry, PasswordPolicy, PasswordPolicyMode};
+
+const CASES: usize = 10_000;
+
+const MODES: [PasswordPolicyMode; 6] = [
+    PasswordPolicyMode::SledRental,
+    PasswordPolicyMode::SledRentalExclusive,
+    PasswordPolicyMode::TobogganCorporate,
+    PasswordPolicyMode::TobogganFromEnd,
+    PasswordPolicyMode::ExactCount,
+    PasswordPolicyMode::Both,
+];
+
+/// A small xorshift generator, good enough to explore inputs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    /// A bound, biased towards small values and the extremes of `u32`.
+    fn bound(&mut self) -> u32 {
+        match self.below(4) {
+            0 => self.below(3) as u32,
+            1 => self.below(30) as u32,
+            2 => u32::MAX - self.below(3) as u32,
+            _ => self.next() as u32,
+        }
+    }
+
+    /// Any character, biased towards ASCII and a few multi-byte ones.
+    fn char(&mut self) -> char {
+        match self.below(4) {
+            0 => (b'a' + self.below(3) as u8) as char,
+            1 => ['é', 'α', '字', '🦀', ' ', '\t', '-', ':'][self.below(8) as usize],
+            2 => (self.below(128) as u8) as char,
+            _ => loop {
+                if let Some(c) = char::from_u32(self.below(0x11_0000) as u32) {
+                    break c;
+                }
+            },
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let length = self.below(12);
+        (0..length).map(|_| self.char()).collect()
+    }
+}
+
+#[test]
+fn test_policy_display_parse_round_trip() {
+    let mut rng = Rng(0x2020_0202);
+
+    for _ in 0..CASES {
+        let pattern = rng.char();
+        if pattern.is_whitespace() || pattern.is_control() {
+            continue;
+        }
+        let (a, b) = (rng.bound().max(1), rng.bound().max(1));
+        let policy = PasswordPolicy::new(pattern, a.min(b), a.max(b));
+
+        assert_eq!(PasswordPolicy::parse(&policy.to_string()), Some(policy));
+    }
+}
+
+#[test]
+fn test_validate_never_panics() {
+    let mut rng = Rng(0xdead_beef);
+
+    for _ in 0..CASES {
+        let policy = PasswordPolicy::new(rng.char(), rng.bound(), rng.bound());
+        let password = rng.string();
+
+        for &mode in &MODES {
+            policy.validate(mode, &password);
+            policy.failure_reason(mode, &password);
+        }
+        let _ = policy.validate_positions(&password);
+    }
+}
+
+#[test]
+fn test_parse_never_panics() {
+    let mut rng = Rng(0x1234_5678);
+
+    for _ in 0..CASES {
+        let line = rng.string();
+        PasswordPolicy::parse(&line);
+        if let Some(entry) = PasswordEntry::parse(&line) {
+            for &mode in &MODES {
+                entry.is_valid(mode);
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use advent_of_code_2020_day_2::{PasswordEntry, PasswordPolicy, PasswordPolicyMode};
+
+#[test]
+fn test_parse_and_validate_entries() {
+    let entry = PasswordEntry::parse("1-3 a: abcde").unwrap();
+    assert_eq!(entry, PasswordEntry::new(PasswordPolicy::new('a', 1, 3), "abcde"));
+    assert!(entry.is_valid(PasswordPolicyMode::SledRental));
+    assert!(entry.is_valid(PasswordPolicyMode::TobogganCorporate));
+
+    let entry = PasswordEntry::parse("2-9 c: ccccccccc").unwrap();
+    assert!(entry.is_valid(PasswordPolicyMode::SledRental));
+    assert!(!entry.is_valid(PasswordPolicyMode::TobogganCorporate));
+
+    assert_eq!(PasswordEntry::parse("1-3 a abcde"), None);
+}
+
+#[test]
+fn test_policy_validate() {
+    let policy = PasswordPolicy::parse("1-3 b").unwrap();
+    assert_eq!(policy.to_string(), "1-3 b");
+    assert!(!policy.validate(PasswordPolicyMode::SledRental, "cdefg"));
+}
@@ -0,0 +1,42 @@
+//! Snapshot tests of the exact output of the binary. Each snapshot is a file under
+//! `tests/snapshots/`; run with `UPDATE_SNAPSHOTS=1` to rewrite them after an intended change.
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+const FIXTURE: &str = "tests/fixtures/snapshot.txt";
+
+/// Runs the binary with `args` and compares its stdout with the snapshot `name`.
+fn assert_snapshot(name: &str, args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_advent-of-code-2020-day-2"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let path = format!("tests/snapshots/{}.snap", name);
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &stdout).unwrap();
+        return;
+    }
+
+    let snapshot = fs::read_to_string(&path).unwrap_or_else(|err| panic!("cannot read {}: {}", path, err));
+    assert_eq!(stdout, snapshot, "output differs from {}", path);
+}
+
+#[test]
+fn test_snapshot_text() {
+    assert_snapshot("text", &[FIXTURE, "--mode", "sled", "--verbose"]);
+}
+
+#[test]
+fn test_snapshot_json() {
+    assert_snapshot("json", &[FIXTURE, "--format", "json"]);
+}
+
+#[test]
+fn test_snapshot_count_only() {
+    assert_snapshot("count_only", &[FIXTURE, "--mode", "sled", "--count-only"]);
+}